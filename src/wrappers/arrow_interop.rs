@@ -0,0 +1,103 @@
+//! Conversions between rank 1 literals and Apache Arrow arrays.
+use super::{ArrayElement, ElementType, Literal, PrimitiveType};
+use crate::{Error, Result};
+use arrow::array::{Array, ArrayRef, AsArray, BooleanArray, PrimitiveArray};
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType, Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+    Int8Type, UInt16Type, UInt32Type, UInt64Type, UInt8Type,
+};
+use std::sync::Arc;
+
+fn to_primitive_array<T>(literal: &Literal) -> Result<ArrayRef>
+where
+    T: ArrowPrimitiveType,
+    T::Native: ArrayElement,
+{
+    let values = literal.to_vec::<T::Native>()?;
+    Ok(Arc::new(PrimitiveArray::<T>::from_iter_values(values)))
+}
+
+fn from_primitive_array<T>(array: &dyn Array) -> Result<Literal>
+where
+    T: ArrowPrimitiveType,
+    T::Native: ArrayElement,
+{
+    let values: &[T::Native] = array.as_primitive::<T>().values();
+    let data = unsafe {
+        std::slice::from_raw_parts(values.as_ptr() as *const u8, std::mem::size_of_val(values))
+    };
+    Literal::create_from_shape_and_untyped_data(
+        <T::Native as ArrayElement>::TY,
+        &[values.len()],
+        data,
+    )
+}
+
+impl Literal {
+    /// Convert a rank 1 literal to an Arrow array using the matching primitive type, `Pred`
+    /// literals are converted to boolean arrays. Half precision and complex element types are
+    /// not supported.
+    pub fn to_arrow(&self) -> Result<ArrayRef> {
+        let shape = self.array_shape()?;
+        if shape.dims().len() != 1 {
+            Err(Error::UnexpectedNumberOfDims {
+                expected: 1,
+                got: shape.dims().len(),
+                dims: shape.dims().to_vec(),
+            })?
+        }
+        match shape.ty() {
+            ElementType::Pred => {
+                let values = self.convert(PrimitiveType::U8)?.to_vec::<u8>()?;
+                Ok(Arc::new(values.into_iter().map(|v| Some(v != 0)).collect::<BooleanArray>()))
+            }
+            ElementType::S8 => to_primitive_array::<Int8Type>(self),
+            ElementType::S16 => to_primitive_array::<Int16Type>(self),
+            ElementType::S32 => to_primitive_array::<Int32Type>(self),
+            ElementType::S64 => to_primitive_array::<Int64Type>(self),
+            ElementType::U8 => to_primitive_array::<UInt8Type>(self),
+            ElementType::U16 => to_primitive_array::<UInt16Type>(self),
+            ElementType::U32 => to_primitive_array::<UInt32Type>(self),
+            ElementType::U64 => to_primitive_array::<UInt64Type>(self),
+            ElementType::F32 => to_primitive_array::<Float32Type>(self),
+            ElementType::F64 => to_primitive_array::<Float64Type>(self),
+            ElementType::F16 | ElementType::Bf16 | ElementType::C64 | ElementType::C128 => {
+                Err(Error::UnsupportedElementType { ty: shape.primitive_type(), op: "to_arrow" })
+            }
+        }
+    }
+
+    /// Create a rank 1 literal from an Arrow array, this is the inverse of
+    /// [`Literal::to_arrow`]. Arrays that contain null values are not supported.
+    pub fn from_arrow(array: &dyn Array) -> Result<Literal> {
+        if array.null_count() > 0 {
+            Err(super::xla_error(format!(
+                "cannot convert arrow array with {} nulls",
+                array.null_count()
+            )))?
+        }
+        match array.data_type() {
+            DataType::Boolean => {
+                let values: Vec<_> =
+                    array.as_boolean().iter().map(|v| v.unwrap_or(false) as u8).collect();
+                Literal::create_from_shape_and_untyped_data(
+                    ElementType::U8,
+                    &[values.len()],
+                    &values,
+                )?
+                .convert(PrimitiveType::Pred)
+            }
+            DataType::Int8 => from_primitive_array::<Int8Type>(array),
+            DataType::Int16 => from_primitive_array::<Int16Type>(array),
+            DataType::Int32 => from_primitive_array::<Int32Type>(array),
+            DataType::Int64 => from_primitive_array::<Int64Type>(array),
+            DataType::UInt8 => from_primitive_array::<UInt8Type>(array),
+            DataType::UInt16 => from_primitive_array::<UInt16Type>(array),
+            DataType::UInt32 => from_primitive_array::<UInt32Type>(array),
+            DataType::UInt64 => from_primitive_array::<UInt64Type>(array),
+            DataType::Float32 => from_primitive_array::<Float32Type>(array),
+            DataType::Float64 => from_primitive_array::<Float64Type>(array),
+            dt => Err(super::xla_error(format!("cannot convert arrow array of type {dt}"))),
+        }
+    }
+}
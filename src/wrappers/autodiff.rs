@@ -0,0 +1,179 @@
+//! A minimal reverse-mode differentiation helper for hand-built computations.
+//!
+//! This is not a general autodiff, the forward pass is expressed through a [`VjpBuilder`] which
+//! records the ops and their backward rules, the reverse-mode computation is then assembled with
+//! [`VjpBuilder::vjp`]. The supported ops are `add`, `mul`, `matmul`, `relu` and `reduce_sum`,
+//! other ops can be used by registering their backward rule with [`VjpBuilder::register`].
+use super::XlaOp;
+use crate::{Error, Result};
+
+/// A handle on a value recorded by a [`VjpBuilder`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct VjpVar(usize);
+
+/// Compute the cotangents of the inputs of an op from the cotangent of its output.
+type BackwardRule = Box<dyn Fn(&XlaOp) -> Result<Vec<XlaOp>>>;
+
+struct Node {
+    op: XlaOp,
+    inputs: Vec<VjpVar>,
+    backward: Option<BackwardRule>,
+}
+
+/// Record a forward computation so that its vector-jacobian product can be built.
+#[derive(Default)]
+pub struct VjpBuilder {
+    nodes: Vec<Node>,
+}
+
+fn check_same_dims(op: &'static str, lhs: &XlaOp, rhs: &XlaOp) -> Result<()> {
+    let lhs_dims = lhs.array_shape()?.dims().to_vec();
+    let rhs_dims = rhs.array_shape()?.dims().to_vec();
+    if lhs_dims != rhs_dims {
+        Err(super::xla_error(format!(
+            "{op} operands must have the same dims, got {lhs_dims:?} and {rhs_dims:?}"
+        )))?
+    }
+    Ok(())
+}
+
+impl VjpBuilder {
+    /// Create a builder with no recorded values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The op associated with a recorded value.
+    pub fn op(&self, var: VjpVar) -> &XlaOp {
+        &self.nodes[var.0].op
+    }
+
+    /// Record an op that gradients can be computed for, typically a parameter.
+    pub fn var(&mut self, op: &XlaOp) -> VjpVar {
+        self.nodes.push(Node { op: op.clone(), inputs: vec![], backward: None });
+        VjpVar(self.nodes.len() - 1)
+    }
+
+    /// Record an op computed from `inputs`. `backward` is called with the cotangent of `op` and
+    /// has to return the cotangents of each of the inputs, in the same order and with the same
+    /// shapes as the inputs.
+    pub fn register<F>(&mut self, op: XlaOp, inputs: &[VjpVar], backward: F) -> VjpVar
+    where
+        F: Fn(&XlaOp) -> Result<Vec<XlaOp>> + 'static,
+    {
+        let node = Node { op, inputs: inputs.to_vec(), backward: Some(Box::new(backward)) };
+        self.nodes.push(node);
+        VjpVar(self.nodes.len() - 1)
+    }
+
+    /// Elementwise addition, both operands must have the same dims.
+    pub fn add(&mut self, lhs: VjpVar, rhs: VjpVar) -> Result<VjpVar> {
+        let (l, r) = (self.op(lhs), self.op(rhs));
+        check_same_dims("add", l, r)?;
+        let op = (l + r)?;
+        Ok(self.register(op, &[lhs, rhs], |g| Ok(vec![g.clone(), g.clone()])))
+    }
+
+    /// Elementwise multiplication, both operands must have the same dims.
+    pub fn mul(&mut self, lhs: VjpVar, rhs: VjpVar) -> Result<VjpVar> {
+        let (l, r) = (self.op(lhs).clone(), self.op(rhs).clone());
+        check_same_dims("mul", &l, &r)?;
+        let op = (&l * &r)?;
+        Ok(self.register(op, &[lhs, rhs], move |g| Ok(vec![(g * &r)?, (g * &l)?])))
+    }
+
+    /// Matrix multiplication, the operands must have the same rank and batch dimensions.
+    pub fn matmul(&mut self, lhs: VjpVar, rhs: VjpVar) -> Result<VjpVar> {
+        let (l, r) = (self.op(lhs).clone(), self.op(rhs).clone());
+        let op = l.matmul(&r)?;
+        let backward = move |g: &XlaOp| {
+            let grad_lhs = g.matmul(&r.swap_dims(-2, -1)?)?;
+            let grad_rhs = l.swap_dims(-2, -1)?.matmul(g)?;
+            Ok(vec![grad_lhs, grad_rhs])
+        };
+        Ok(self.register(op, &[lhs, rhs], backward))
+    }
+
+    /// Rectified linear unit, `max(x, 0)`. The gradient at 0 is taken to be 0.
+    pub fn relu(&mut self, x: VjpVar) -> Result<VjpVar> {
+        let x_op = self.op(x);
+        let zero = x_op.builder().zero(x_op.array_shape()?.ty())?;
+        let mask = x_op.gt(&zero)?.convert(x_op.ty()?)?;
+        let op = x_op.max(&zero)?;
+        Ok(self.register(op, &[x], move |g| Ok(vec![(g * &mask)?])))
+    }
+
+    /// Sum over the specified dimensions, the reduced dimensions are not kept.
+    pub fn reduce_sum(&mut self, x: VjpVar, dims: &[i64]) -> Result<VjpVar> {
+        let x_op = self.op(x);
+        let x_dims = x_op.array_shape()?.dims().to_vec();
+        let rank = x_dims.len() as i64;
+        let mut reduced = vec![false; x_dims.len()];
+        for &dim in dims.iter() {
+            let d = if dim < 0 { dim + rank } else { dim };
+            if d < 0 || d >= rank {
+                Err(Error::IndexOutOfBounds { index: dim, rank: x_dims.len() })?
+            }
+            reduced[d as usize] = true
+        }
+        let kept_dims: Vec<i64> = (0..rank).filter(|&d| !reduced[d as usize]).collect();
+        let op = x_op.reduce_sum(dims, false)?;
+        let backward = move |g: &XlaOp| Ok(vec![g.broadcast_in_dim(&x_dims, &kept_dims)?]);
+        Ok(self.register(op, &[x], backward))
+    }
+
+    /// Build the vector-jacobian product of `output` with respect to the `wrt` values, i.e. the
+    /// cotangent of each of the `wrt` values given the cotangent of `output`. Values that
+    /// `output` does not depend on get a zero cotangent.
+    pub fn vjp(&self, output: VjpVar, cotangent: &XlaOp, wrt: &[VjpVar]) -> Result<Vec<XlaOp>> {
+        let mut grads: Vec<Option<XlaOp>> = vec![None; output.0 + 1];
+        grads[output.0] = Some(cotangent.clone());
+        for index in (0..=output.0).rev() {
+            let node = &self.nodes[index];
+            let (grad, backward) = match (&grads[index], &node.backward) {
+                (Some(grad), Some(backward)) => (grad, backward),
+                _ => continue,
+            };
+            let input_grads = backward(grad)?;
+            if input_grads.len() != node.inputs.len() {
+                Err(super::xla_error(format!(
+                    "backward rule returned {} cotangents for {} inputs",
+                    input_grads.len(),
+                    node.inputs.len()
+                )))?
+            }
+            for (input, input_grad) in node.inputs.iter().zip(input_grads) {
+                let acc = match grads[input.0].take() {
+                    None => input_grad,
+                    Some(acc) => (acc + input_grad)?,
+                };
+                grads[input.0] = Some(acc)
+            }
+        }
+        wrt.iter()
+            .map(|var| match grads.get(var.0).and_then(|g| g.clone()) {
+                Some(grad) => Ok(grad),
+                None => {
+                    let op = self.op(*var);
+                    let shape = op.array_shape()?;
+                    op.builder().zero(shape.ty())?.broadcast(shape.dims())
+                }
+            })
+            .collect()
+    }
+
+    /// The gradient of a scalar `output` with respect to the `wrt` values.
+    pub fn grad(&self, output: VjpVar, wrt: &[VjpVar]) -> Result<Vec<XlaOp>> {
+        let op = self.op(output);
+        let shape = op.array_shape()?;
+        if !shape.dims().is_empty() {
+            Err(Error::UnexpectedNumberOfDims {
+                expected: 0,
+                got: shape.dims().len(),
+                dims: shape.dims().to_vec(),
+            })?
+        }
+        let one = op.builder().one(shape.ty())?;
+        self.vjp(output, &one, wrt)
+    }
+}
@@ -0,0 +1,38 @@
+//! Helpers creating builders and constants on top of the basic [`XlaBuilder`] methods.
+use super::{Literal, XlaBuilder, XlaOp};
+use crate::Result;
+
+impl XlaBuilder {
+    /// Create a builder for a nested computation, e.g. the condition or body of a while loop,
+    /// a reducer, or a computation to be called. The sub-builder is named `{parent}.{name}`.
+    /// Ops created with the sub-builder cannot be mixed with the ops of the parent builder,
+    /// instead the sub-builder `build` method returns an [`super::XlaComputation`] that can be
+    /// passed to the parent ops.
+    pub fn create_sub_builder(&self, name: &str) -> XlaBuilder {
+        XlaBuilder::new(&format!("{}.{name}", self.name()))
+    }
+
+    /// Create a constant op from a literal, if the literal has at least `min_splat_elements`
+    /// elements that all have the same value a scalar constant is broadcasted rather than
+    /// embedding the whole literal. This keeps the computation small for large constants such
+    /// as all-zeros initializers.
+    pub fn constant_literal_splat(
+        &self,
+        literal: &Literal,
+        min_splat_elements: usize,
+    ) -> Result<XlaOp> {
+        let shape = literal.array_shape()?;
+        let element_count = shape.element_count();
+        if element_count < min_splat_elements || element_count == 0 {
+            return self.constant_literal(literal);
+        }
+        let data = literal.untyped_data();
+        let element_size = shape.ty().element_size_in_bytes();
+        let (first, rest) = data.split_at(element_size);
+        if rest.chunks_exact(element_size).any(|v| v != first) {
+            return self.constant_literal(literal);
+        }
+        let scalar = Literal::create_from_shape_and_untyped_data(shape.ty(), &[], first)?;
+        self.constant_literal(&scalar)?.broadcast(shape.dims())
+    }
+}
@@ -0,0 +1,76 @@
+//! Integer arithmetic with overflow detection.
+//!
+//! XLA integer operations silently wrap around on overflow, the functions in this module return
+//! the wrapped result together with a `Pred` op of the same shape that is true for the elements
+//! where an overflow happened. Both operands must have the same integer element type. The
+//! detection adds a few elementwise ops per operation, and a division for multiplications, so
+//! these should only be used where overflows are a concern, e.g. for index computations. The
+//! flags can be reduced and returned as an output of the computation, or sent to the outfeed,
+//! to report overflows.
+use super::{ElementType, XlaOp};
+use crate::{Error, Result};
+
+fn integer_type(op: &XlaOp, op_name: &'static str) -> Result<ElementType> {
+    let ty = op.array_shape()?.ty();
+    if !ty.is_signed_int() && !ty.is_unsigned_int() {
+        Err(Error::UnsupportedElementType { ty: ty.primitive_type(), op: op_name })?
+    }
+    Ok(ty)
+}
+
+impl XlaOp {
+    /// Add two integer ops, returning the result and the per-element overflow flags.
+    pub fn add_checked(&self, rhs: &XlaOp) -> Result<(XlaOp, XlaOp)> {
+        let ty = integer_type(self, "add_checked")?;
+        let result = self.add_(rhs)?;
+        let overflow = if ty.is_signed_int() {
+            // The operands have the same sign and the result has a different one.
+            let zero = self.builder().zero(ty)?;
+            let lhs_neg = self.lt(&zero)?;
+            lhs_neg.eq(&rhs.lt(&zero)?)?.and(&result.lt(&zero)?.ne(&lhs_neg)?)?
+        } else {
+            result.lt(self)?
+        };
+        Ok((result, overflow))
+    }
+
+    /// Subtract two integer ops, returning the result and the per-element overflow flags.
+    pub fn sub_checked(&self, rhs: &XlaOp) -> Result<(XlaOp, XlaOp)> {
+        let ty = integer_type(self, "sub_checked")?;
+        let result = self.sub_(rhs)?;
+        let overflow = if ty.is_signed_int() {
+            // The operands have different signs and the result does not have the sign of `self`.
+            let zero = self.builder().zero(ty)?;
+            let lhs_neg = self.lt(&zero)?;
+            lhs_neg.ne(&rhs.lt(&zero)?)?.and(&result.lt(&zero)?.ne(&lhs_neg)?)?
+        } else {
+            self.lt(rhs)?
+        };
+        Ok((result, overflow))
+    }
+
+    /// Multiply two integer ops, returning the result and the per-element overflow flags.
+    pub fn mul_checked(&self, rhs: &XlaOp) -> Result<(XlaOp, XlaOp)> {
+        let ty = integer_type(self, "mul_checked")?;
+        let b = self.builder();
+        let zero = b.zero(ty)?;
+        let one = b.one(ty)?;
+        let result = self.mul_(rhs)?;
+        // The product overflowed iff dividing it by a non-zero `self` does not give back `rhs`.
+        let lhs_is_zero = self.eq(&zero)?;
+        let ones = one.broadcast(self.array_shape()?.dims())?;
+        let divisor = lhs_is_zero.select(&ones, self)?;
+        let overflow = self.ne(&zero)?.and(&result.div_(&divisor)?.ne(rhs)?)?;
+        let overflow = if ty.is_signed_int() {
+            // `-1 * MIN` wraps to `MIN` and `MIN / -1` also wraps to `MIN`, so this case is not
+            // caught by the division check.
+            let minus_one = zero.sub_(&one)?;
+            let lhs_is_minus_one = self.eq(&minus_one)?;
+            let wrapped = result.eq(rhs)?.and(&rhs.ne(&zero)?)?;
+            overflow.or(&lhs_is_minus_one.and(&wrapped)?)?
+        } else {
+            overflow
+        };
+        Ok((result, overflow))
+    }
+}
@@ -0,0 +1,145 @@
+//! Comparisons between ops that go beyond the elementwise comparison primitives.
+use super::{PrimitiveType, XlaOp};
+use crate::Result;
+
+/// The comparison to perform between two ops, see [`XlaOp::compare`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompareDirection {
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+    Le,
+    Lt,
+}
+
+/// How floating point values get compared, see [`XlaOp::compare`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ComparisonType {
+    /// The IEEE 754 semantics: `NaN` compares unequal to everything, including itself, and
+    /// `-0.0` is equal to `+0.0`.
+    #[default]
+    Float,
+    /// A total order where `-0.0 < +0.0` and `NaN` is greater than every other value and equal to
+    /// itself, this is the order to use when sorting values that can contain `NaN`.
+    TotalOrder,
+}
+
+impl XlaOp {
+    /// `self < rhs` using the total order of [`ComparisonType::TotalOrder`].
+    fn lt_total_order(&self, rhs: &XlaOp) -> Result<XlaOp> {
+        let b = self.builder();
+        let ty = self.ty()?;
+        let zero = b.c0(0f32)?.convert(ty)?;
+        let one = b.c0(1f32)?.convert(ty)?;
+        // The sign of a zero can only be observed through its inverse, `1 / -0.0` is `-inf`.
+        let negative_zero = (&one / self)?.lt(&zero)?;
+        let positive_zero = (&one / rhs)?.gt(&zero)?;
+        let zeros_lt =
+            self.eq(rhs)?.and(&self.eq(&zero)?)?.and(&negative_zero.and(&positive_zero)?)?;
+        let nan_lt = self.eq(self)?.and(&rhs.ne(rhs)?)?;
+        self.lt(rhs)?.or(&zeros_lt)?.or(&nan_lt)
+    }
+
+    /// `self == rhs` using the total order of [`ComparisonType::TotalOrder`].
+    fn eq_total_order(&self, rhs: &XlaOp) -> Result<XlaOp> {
+        let b = self.builder();
+        let ty = self.ty()?;
+        let zero = b.c0(0f32)?.convert(ty)?;
+        let one = b.c0(1f32)?.convert(ty)?;
+        let same_sign = (&one / self)?.lt(&zero)?.eq(&(&one / rhs)?.lt(&zero)?)?;
+        let both_nan = self.ne(self)?.and(&rhs.ne(rhs)?)?;
+        self.eq(rhs)?.and(&self.ne(&zero)?.or(&same_sign)?)?.or(&both_nan)
+    }
+
+    /// Compare two ops elementwise, returning a `Pred` op. With [`ComparisonType::TotalOrder`],
+    /// floating point values are compared using a total order rather than the IEEE 754 semantics,
+    /// for other element types both comparison types are equivalent. `NaN` values are all
+    /// considered as positive, their sign is not taken into account.
+    pub fn compare(
+        &self,
+        rhs: &XlaOp,
+        direction: CompareDirection,
+        comparison_type: ComparisonType,
+    ) -> Result<XlaOp> {
+        let is_float = self.array_shape()?.ty().is_float();
+        if comparison_type == ComparisonType::Float || !is_float {
+            return match direction {
+                CompareDirection::Eq => self.eq(rhs),
+                CompareDirection::Ne => self.ne(rhs),
+                CompareDirection::Ge => self.ge(rhs),
+                CompareDirection::Gt => self.gt(rhs),
+                CompareDirection::Le => self.le(rhs),
+                CompareDirection::Lt => self.lt(rhs),
+            };
+        }
+        match direction {
+            CompareDirection::Eq => self.eq_total_order(rhs),
+            CompareDirection::Ne => self.lt_total_order(rhs)?.or(&rhs.lt_total_order(self)?),
+            CompareDirection::Ge => rhs.lt_total_order(self)?.or(&self.eq_total_order(rhs)?),
+            CompareDirection::Gt => rhs.lt_total_order(self),
+            CompareDirection::Le => self.lt_total_order(rhs)?.or(&self.eq_total_order(rhs)?),
+            CompareDirection::Lt => self.lt_total_order(rhs),
+        }
+    }
+
+    /// Broadcast `self` and `rhs` to their common shape, using the NumPy broadcasting rules:
+    /// dimensions are aligned on the right and dimensions of size 1 are expanded.
+    pub(super) fn broadcast_with(&self, rhs: &XlaOp) -> Result<(XlaOp, XlaOp)> {
+        let lhs_dims = self.array_shape()?.dims().to_vec();
+        let rhs_dims = rhs.array_shape()?.dims().to_vec();
+        if lhs_dims == rhs_dims {
+            return Ok((self.clone(), rhs.clone()));
+        }
+        let rank = usize::max(lhs_dims.len(), rhs_dims.len());
+        let mut out_dims = vec![1i64; rank];
+        for dims in [&lhs_dims, &rhs_dims] {
+            let offset = rank - dims.len();
+            for (i, &d) in dims.iter().enumerate() {
+                let o = &mut out_dims[offset + i];
+                if *o == 1 {
+                    *o = d
+                } else if d != 1 && d != *o {
+                    let msg = format!("cannot broadcast {lhs_dims:?} with {rhs_dims:?}");
+                    Err(super::xla_error(msg))?
+                }
+            }
+        }
+        let broadcast = |op: &XlaOp, dims: &[i64]| {
+            if dims == out_dims {
+                return Ok(op.clone());
+            }
+            let offset = (rank - dims.len()) as i64;
+            let broadcast_dims: Vec<_> = (0..dims.len() as i64).map(|i| i + offset).collect();
+            op.broadcast_in_dim(&out_dims, &broadcast_dims)
+        };
+        Ok((broadcast(self, &lhs_dims)?, broadcast(rhs, &rhs_dims)?))
+    }
+
+    /// A `Pred` scalar that is true iff all the elements of `self` are close to the ones of
+    /// `other`, i.e. `|self - other| <= atol + rtol * |other|` as in `numpy.allclose`. The two
+    /// ops get broadcasted to a common shape, and promoted to a common element type with
+    /// integer and `Pred` ops compared as `F64`. Equal values, including infinities with the
+    /// same sign, are always close while `NaN` values are never close.
+    pub fn all_close(&self, other: &XlaOp, rtol: f64, atol: f64) -> Result<XlaOp> {
+        let b = self.builder();
+        let (lhs, rhs) = self.promote_with(other)?;
+        let (lhs, rhs) = lhs.broadcast_with(&rhs)?;
+        let ty = lhs.array_shape()?.ty();
+        let (lhs, rhs) = if ty.is_float() || ty.is_complex() {
+            (lhs, rhs)
+        } else {
+            (lhs.convert(PrimitiveType::F64)?, rhs.convert(PrimitiveType::F64)?)
+        };
+        let diff = (&lhs - &rhs)?.abs()?;
+        let tol_ty = diff.ty()?;
+        let atol = b.c0(atol)?.convert(tol_ty)?;
+        let rtol = b.c0(rtol)?.convert(tol_ty)?;
+        let tol = (atol + (rtol * rhs.abs()?)?)?;
+        let element_count = diff.array_shape()?.element_count() as i64;
+        let all_dims: Vec<_> = (0..diff.rank()? as i64).collect();
+        let is_close = diff.le(&tol)?.or(&lhs.eq(&rhs)?)?;
+        let close_count = is_close.convert(PrimitiveType::S64)?.reduce_sum(&all_dims, false)?;
+        close_count.eq(&b.c0(element_count)?)
+    }
+}
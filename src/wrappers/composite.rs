@@ -0,0 +1,56 @@
+//! Named reusable subgraph builders.
+use super::{XlaBuilder, XlaOp};
+use crate::Result;
+use std::collections::HashMap;
+
+/// A function building a subgraph from its operands.
+pub type CompositeBuilder = Box<dyn Fn(&XlaBuilder, &[XlaOp]) -> Result<XlaOp>>;
+
+/// A registry of named subgraph builders, library authors can register their composite ops once
+/// and have them applied by name when building computations. Only the decomposition is
+/// recorded: applying a composite inlines the ops it builds, the name is only used as a key in
+/// the registry and no XLA composite metadata (name, attributes, version) is attached to the
+/// emitted ops, as the bindings do not expose composite calls.
+#[derive(Default)]
+pub struct CompositeRegistry {
+    composites: HashMap<String, CompositeBuilder>,
+}
+
+impl CompositeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new composite, this returns an error if a composite with the same name has
+    /// already been registered.
+    pub fn register_composite(&mut self, name: &str, build: CompositeBuilder) -> Result<()> {
+        if self.composites.contains_key(name) {
+            let msg = format!("composite {name} has already been registered");
+            Err(super::xla_error(msg))?
+        }
+        self.composites.insert(name.to_string(), build);
+        Ok(())
+    }
+
+    /// Build the subgraph of a registered composite with `builder`, using `operands` as inputs.
+    /// The ops are added directly to `builder`, without any boundary marking the composite.
+    pub fn apply_composite(
+        &self,
+        builder: &XlaBuilder,
+        name: &str,
+        operands: &[XlaOp],
+    ) -> Result<XlaOp> {
+        match self.composites.get(name) {
+            Some(build) => build(builder, operands),
+            None => {
+                let msg = format!("unknown composite {name}");
+                Err(super::xla_error(msg))
+            }
+        }
+    }
+
+    /// The names of the registered composites, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.composites.keys().map(|name| name.as_str())
+    }
+}
@@ -0,0 +1,24 @@
+//! One-shot evaluation of computations, mostly useful for experiments and tests.
+use super::{Literal, PjRtClient, XlaBuilder, XlaOp};
+use crate::Result;
+
+/// Build, compile and run a computation with multiple outputs. `build` gets called with a
+/// builder and one parameter op per input, with the same shape as the input, and returns the
+/// roots of the computation. The roots are wrapped in a tuple, the computation is compiled for
+/// `client` and executed on `inputs`, and the returned literals are the values of the roots in
+/// the same order.
+pub fn eval_multi<F>(client: &PjRtClient, build: F, inputs: &[&Literal]) -> Result<Vec<Literal>>
+where
+    F: FnOnce(&XlaBuilder, &[XlaOp]) -> Result<Vec<XlaOp>>,
+{
+    let builder = XlaBuilder::new("eval_multi");
+    let parameters = inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| builder.parameter_s(i as i64, &input.shape()?, &format!("p{i}")))
+        .collect::<Result<Vec<_>>>()?;
+    let roots = build(&builder, &parameters)?;
+    let exe = builder.tuple(&roots)?.build()?.compile(client)?;
+    let output = exe.execute(inputs)?[0][0].to_literal_sync()?;
+    output.to_tuple()
+}
@@ -0,0 +1,151 @@
+//! Helpers to select elements of an op based on indexes, or to build ops from indexes.
+use super::{ElementType, PrimitiveType, XlaBuilder, XlaOp};
+use crate::{Error, Result};
+
+/// Clamp each coordinate held in the last dimension of `indices` to `[0, dims[i] - 1]`.
+fn clamp_coordinates(indices: &XlaOp, dims: &[i64]) -> Result<XlaOp> {
+    let b = indices.builder();
+    let shape = indices.array_shape()?;
+    let last_dim = shape.dims().len() as i64 - 1;
+    let upper: Vec<i64> = dims.iter().map(|d| d - 1).collect();
+    let upper = b.c1(&upper)?.convert(shape.primitive_type())?;
+    let upper = upper.broadcast_in_dim(shape.dims(), &[last_dim])?;
+    indices.min(&upper)?.max(&b.zero(shape.ty())?)
+}
+
+impl XlaBuilder {
+    /// Create a dense tensor of shape `dense_shape` filled with `default_value`, except at the
+    /// positions listed in `indices` which get the corresponding element of `values`.
+    /// `indices` has shape `[n, dense_shape.len()]`, `values` has shape `[n]` and
+    /// `default_value` is a scalar. The values are written in order so for duplicated indices the
+    /// last value is kept. Each coordinate that is out of range gets clamped to its dimension,
+    /// e.g. `[0, 5]` writes to `[0, 2]` for a `[3, 3]` dense shape.
+    pub fn sparse_to_dense(
+        &self,
+        indices: &XlaOp,
+        values: &XlaOp,
+        dense_shape: &[i64],
+        default_value: &XlaOp,
+    ) -> Result<XlaOp> {
+        let indices_shape = indices.array_shape()?;
+        let (n, k) = <(i64, i64)>::try_from(&indices_shape)?;
+        if k as usize != dense_shape.len() {
+            Err(Error::UnexpectedNumberOfDims {
+                expected: dense_shape.len(),
+                got: k as usize,
+                dims: indices_shape.dims().to_vec(),
+            })?
+        }
+        let size: i64 = dense_shape.iter().product();
+        let mut strides = vec![1i64; dense_shape.len()];
+        for i in (0..dense_shape.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * dense_shape[i + 1];
+        }
+        let strides = self.c1(&strides)?;
+        let indices = clamp_coordinates(&indices.convert(PrimitiveType::S64)?, dense_shape)?;
+        let flat_indices = indices.dot_general(&strides, &[1], &[0], &[], &[])?;
+        let dense = default_value.broadcast(&[size])?;
+        // The values are written one at a time by a while loop with state
+        // `(i, dense, flat_indices, values)`, rather than building a `[n, size]` one-hot mask.
+        let init =
+            self.tuple(&[self.zero(ElementType::S64)?, dense, flat_indices, values.clone()])?;
+        let state_shape = self.get_shape(&init)?;
+        let cond = self.create_sub_builder("sparse_to_dense_cond");
+        let state = cond.parameter_s(0, &state_shape, "state")?;
+        let cond = state.get_tuple_element(0)?.lt(&cond.c0(n)?)?.build()?;
+        let body = self.create_sub_builder("sparse_to_dense_body");
+        let state = body.parameter_s(0, &state_shape, "state")?;
+        let i = state.get_tuple_element(0)?;
+        let dense = state.get_tuple_element(1)?;
+        let flat_indices = state.get_tuple_element(2)?;
+        let values = state.get_tuple_element(3)?;
+        let index = flat_indices.dynamic_slice(&[i.clone()], &[1])?.reshape(&[])?;
+        let value = values.dynamic_slice(&[i.clone()], &[1])?;
+        let dense = dense.dynamic_update_slice(&value, &[index])?;
+        let next_i = (i + body.one(ElementType::S64)?)?;
+        let body = body.tuple(&[next_i, dense, flat_indices, values])?.build()?;
+        XlaOp::while_(cond, body, init)?.get_tuple_element(1)?.reshape(dense_shape)
+    }
+}
+
+impl XlaOp {
+    /// Return the positions of the non-zero (or true) elements together with their number.
+    /// As shapes have to be static, the indexes op always has shape `[max_count]`: the first
+    /// `count` elements hold the positions in increasing order and the remaining ones are set
+    /// to -1. If there are more than `max_count` non-zero elements, only the first `max_count`
+    /// ones are returned and `count` is `max_count`. Both ops use `S64`, for ops with more than
+    /// one dimension the positions are the indexes in the flattened (row-major) op. For an op
+    /// with `n` elements this performs `O((n + max_count) log n)` work.
+    pub fn nonzero(&self, max_count: i64) -> Result<(XlaOp, XlaOp)> {
+        let b = self.builder();
+        let shape = self.array_shape()?;
+        let n = shape.element_count() as i64;
+        let s64 = ElementType::S64;
+        let zero = b.zero(s64)?;
+        let one = b.one(s64)?;
+        let x = self.reshape(&[n])?;
+        let is_nonzero = x.ne(&b.zero(shape.ty())?)?.convert(PrimitiveType::S64)?;
+        // cumsum[i] is the number of non-zero elements in 0..=i, computed with a log-step scan
+        // where each step adds a shifted copy of the partial sums.
+        let mut cumsum = is_nonzero.clone();
+        let mut shift = 1;
+        while shift < n {
+            let shifted = cumsum.slice_in_dim1(0, n - shift, 0)?.pad_in_dim(&zero, 0, shift, 0)?;
+            cumsum = (cumsum + shifted)?;
+            shift *= 2
+        }
+        // The position of the k-th non-zero element is the number of elements i such that
+        // cumsum[i] < k, as cumsum is non-decreasing this is found by a binary search.
+        let targets = (b.iota(s64, &[max_count], 0)? + &one)?;
+        let n_op = b.c0(n)?;
+        let mut indexes = zero.broadcast(&[max_count])?;
+        let mut step = if n > 0 { 1i64 << (63 - n.leading_zeros()) } else { 0 };
+        while step > 0 {
+            let candidates = (&indexes + b.c0(step)?)?;
+            let is_valid = candidates.le(&n_op)?;
+            let values = cumsum.take(&(candidates.min(&n_op)? - &one)?, 0)?;
+            let advance = is_valid.and(&values.lt(&targets)?)?;
+            indexes = advance.select(&candidates, &indexes)?;
+            step /= 2
+        }
+        let found = indexes.lt(&n_op)?;
+        let padding = b.c0(-1i64)?.broadcast(&[max_count])?;
+        let indexes = found.select(&indexes, &padding)?;
+        let count = is_nonzero.reduce_sum(&[0], false)?.min(&b.c0(max_count)?)?;
+        Ok((indexes, count))
+    }
+
+    /// Gather slices using coordinates into the leading dimensions of the op, this has the same
+    /// semantics as TensorFlow `gather_nd`. The last dimension of `indices` has size `k` and
+    /// holds coordinates into the first `k` dimensions of `self`, for `indices` of shape
+    /// `[.., k]` and `self` of shape `[d0, .., dk-1, ..]` the result has shape
+    /// `[.., dk, ..]`. Unlike TensorFlow, out of range indices do not return an error: each
+    /// coordinate gets clamped to its dimension, e.g. `[0, 3]` reads `[0, 2]` on a `[3, 3]` op.
+    pub fn gather_nd(&self, indices: &XlaOp) -> Result<XlaOp> {
+        let b = self.builder();
+        let shape = self.array_shape()?;
+        let dims = shape.dims();
+        let indices_shape = indices.array_shape()?;
+        let k = match indices_shape.last_dim() {
+            None => Err(Error::UnexpectedNumberOfDims {
+                expected: 1,
+                got: 0,
+                dims: indices_shape.dims().to_vec(),
+            })?,
+            Some(k) => k,
+        };
+        if k < 1 || k as usize > dims.len() {
+            Err(Error::IndexOutOfBounds { index: k, rank: dims.len() })?
+        }
+        let k = k as usize;
+        let mut strides = vec![1i64; k];
+        for i in (0..k - 1).rev() {
+            strides[i] = strides[i + 1] * dims[i + 1];
+        }
+        let strides = b.c1(&strides)?.convert(indices_shape.primitive_type())?;
+        let last_dim = indices_shape.dims().len() as i64 - 1;
+        let indices = clamp_coordinates(indices, &dims[..k])?;
+        let flat_indices = indices.dot_general(&strides, &[last_dim], &[0], &[], &[])?;
+        self.flatten_leading(k as i64)?.take(&flat_indices, 0)
+    }
+}
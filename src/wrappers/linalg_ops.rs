@@ -0,0 +1,158 @@
+//! Linear algebra helpers built on top of `dot_general` and the basic shape manipulation ops.
+use super::{ElementType, XlaBuilder, XlaOp};
+use crate::{Error, Result};
+
+impl XlaOp {
+    /// A `dot_general` contraction where the accumulation happens in `accumulation_type`, e.g.
+    /// `Bf16` operands with `F32` accumulation. Both operands are converted to
+    /// `accumulation_type` if needed and the result uses this type too.
+    pub fn dot_general_mixed(
+        &self,
+        rhs: &XlaOp,
+        lhs_contracting_dims: &[i64],
+        rhs_contracting_dims: &[i64],
+        lhs_batch_dims: &[i64],
+        rhs_batch_dims: &[i64],
+        accumulation_type: ElementType,
+    ) -> Result<Self> {
+        let convert = |op: &XlaOp| -> Result<XlaOp> {
+            if op.array_shape()?.ty() == accumulation_type {
+                Ok(op.clone())
+            } else {
+                op.convert(accumulation_type.primitive_type())
+            }
+        };
+        let lhs = convert(self)?;
+        let rhs = convert(rhs)?;
+        lhs.dot_general(
+            &rhs,
+            lhs_contracting_dims,
+            rhs_contracting_dims,
+            lhs_batch_dims,
+            rhs_batch_dims,
+        )
+    }
+
+    /// Prepare `self` and `rhs` so that `lhs.matmul(&rhs)` contracts the expected dimensions,
+    /// i.e. `lhs` has shape `[.., m, k]` and `rhs` has shape `[.., k, n]`. When the trailing
+    /// dimensions do not line up this way, the operand(s) given as `[.., k, m]` or `[.., n, k]`
+    /// get their last two dimensions swapped, the shapes that already line up are left as is.
+    /// The leading batch dimensions are broadcasted to a common shape using the numpy
+    /// broadcasting rules.
+    pub fn align_for_matmul(&self, rhs: &XlaOp) -> Result<(XlaOp, XlaOp)> {
+        let lhs_dims = self.array_shape()?.dims().to_vec();
+        let rhs_dims = rhs.array_shape()?.dims().to_vec();
+        let err = |msg| Error::MatMulIncorrectDims {
+            lhs_dims: lhs_dims.clone(),
+            rhs_dims: rhs_dims.clone(),
+            msg,
+        };
+        let (lhs_rank, rhs_rank) = (lhs_dims.len(), rhs_dims.len());
+        if lhs_rank < 2 || rhs_rank < 2 {
+            Err(err("operands should have at least two dimensions"))?
+        }
+        let (l0, l1) = (lhs_dims[lhs_rank - 2], lhs_dims[lhs_rank - 1]);
+        let (r0, r1) = (rhs_dims[rhs_rank - 2], rhs_dims[rhs_rank - 1]);
+        let (swap_lhs, swap_rhs) = if l1 == r0 {
+            (false, false)
+        } else if l1 == r1 {
+            (false, true)
+        } else if l0 == r0 {
+            (true, false)
+        } else if l0 == r1 {
+            (true, true)
+        } else {
+            Err(err("no pair of trailing dimensions can be contracted"))?
+        };
+        let batch_rank = usize::max(lhs_rank, rhs_rank) - 2;
+        let mut batch_dims = vec![1i64; batch_rank];
+        for (dims, rank) in [(&lhs_dims, lhs_rank), (&rhs_dims, rhs_rank)] {
+            let offset = batch_rank + 2 - rank;
+            for (i, &d) in dims[..rank - 2].iter().enumerate() {
+                let b = &mut batch_dims[offset + i];
+                if *b == 1 {
+                    *b = d
+                } else if d != 1 && d != *b {
+                    Err(err("batch dimensions cannot be broadcasted"))?
+                }
+            }
+        }
+        let align = |op: &XlaOp, dims: &[i64], swap: bool| -> Result<XlaOp> {
+            let rank = dims.len();
+            let op = if swap { op.swap_dims(-2, -1)? } else { op.clone() };
+            let (m0, m1) = if swap {
+                (dims[rank - 1], dims[rank - 2])
+            } else {
+                (dims[rank - 2], dims[rank - 1])
+            };
+            let mut out_dims = batch_dims.clone();
+            out_dims.push(m0);
+            out_dims.push(m1);
+            if out_dims.len() == rank && out_dims[..rank - 2] == dims[..rank - 2] {
+                return Ok(op);
+            }
+            let offset = (batch_rank + 2 - rank) as i64;
+            let broadcast_dims: Vec<_> = (0..rank as i64).map(|i| i + offset).collect();
+            op.broadcast_in_dim(&out_dims, &broadcast_dims)
+        };
+        Ok((align(self, &lhs_dims, swap_lhs)?, align(rhs, &rhs_dims, swap_rhs)?))
+    }
+
+    /// Extract a diagonal of the last two dimensions, for an op of shape `[.., m, n]` the result
+    /// has shape `[.., len]` and contains the elements `x[.., i, i + offset]`. A positive
+    /// `offset` selects a diagonal above the main one, a negative one a diagonal below it.
+    pub fn diagonal(&self, offset: i64) -> Result<Self> {
+        let b = self.builder();
+        let shape = self.array_shape()?;
+        let dims = shape.dims();
+        let rank = dims.len();
+        if rank < 2 {
+            Err(Error::UnexpectedNumberOfDims { expected: 2, got: rank, dims: dims.to_vec() })?
+        }
+        let (m, n) = (dims[rank - 2], dims[rank - 1]);
+        let len = if offset >= 0 { i64::min(m, n - offset) } else { i64::min(m + offset, n) };
+        let len = i64::max(len, 0);
+        let rows = b.iota(ElementType::S64, dims, rank as i64 - 2)?;
+        let cols = b.iota(ElementType::S64, dims, rank as i64 - 1)?;
+        let offset_op = b.c0(offset)?;
+        let mask = (cols - rows)?.eq(&offset_op)?;
+        let zeros = b.zero(shape.ty())?.broadcast(dims)?;
+        let masked = mask.select(self, &zeros)?;
+        // Each row (resp. column) contains at most a single diagonal element when the offset is
+        // non-negative (resp. negative), so summing over columns (resp. rows) extracts it.
+        if offset >= 0 {
+            masked.reduce_sum(&[rank as i64 - 1], false)?.slice_in_dim1(0, len, -1)
+        } else {
+            masked.reduce_sum(&[rank as i64 - 2], false)?.slice_in_dim1(0, len, -1)
+        }
+    }
+
+    /// The sum of the main diagonal of the last two dimensions, these dimensions must have the
+    /// same size. For an op of shape `[.., n, n]` the result has shape `[..]`.
+    pub fn trace(&self) -> Result<Self> {
+        let shape = self.array_shape()?;
+        let dims = shape.dims();
+        let rank = dims.len();
+        if rank < 2 {
+            Err(Error::UnexpectedNumberOfDims { expected: 2, got: rank, dims: dims.to_vec() })?
+        }
+        if dims[rank - 2] != dims[rank - 1] {
+            let msg = format!("trace expects square matrices, got dims {dims:?}");
+            Err(super::xla_error(msg))?
+        }
+        self.diagonal(0)?.reduce_sum(&[rank as i64 - 2], false)
+    }
+}
+
+impl XlaBuilder {
+    /// Create a square matrix with `values` on the main diagonal and zeros elsewhere, `values`
+    /// has to be a rank 1 op.
+    pub fn diag(&self, values: &XlaOp) -> Result<XlaOp> {
+        let shape = values.array_shape()?;
+        let n = i64::try_from(&shape)?;
+        let rows = self.iota(ElementType::S64, &[n, n], 0)?;
+        let cols = self.iota(ElementType::S64, &[n, n], 1)?;
+        let zeros = self.zero(shape.ty())?.broadcast(&[n, n])?;
+        rows.eq(&cols)?.select(&values.broadcast_in_dim(&[n, n], &[1])?, &zeros)
+    }
+}
@@ -50,6 +50,36 @@ impl Literal {
         Ok(Self(v))
     }
 
+    /// Create a literal with shape `shape` from the raw little-endian data stored in a file, the
+    /// file size has to match the size of the shape in bytes. The file is memory-mapped so that
+    /// the data gets copied directly into the literal without an intermediate host buffer. The
+    /// literal owns its data and does not depend on the file afterwards. The file must not be
+    /// modified while this function runs. This returns an error on big-endian hosts.
+    pub fn from_mmap<P: AsRef<std::path::Path>>(path: P, shape: &Shape) -> Result<Self> {
+        if cfg!(target_endian = "big") {
+            Err(super::xla_error("from_mmap is only supported on little-endian hosts".to_string()))?
+        }
+        let shape = ArrayShape::try_from(shape)?;
+        let file = std::fs::File::open(path.as_ref())?;
+        let size_in_bytes = shape.element_count() * shape.ty().element_size_in_bytes();
+        let file_len = file.metadata()?.len() as usize;
+        if file_len != size_in_bytes {
+            let msg = format!(
+                "{:?} has {file_len} bytes, expected {size_in_bytes} bytes for {shape:?}",
+                path.as_ref()
+            );
+            Err(super::xla_error(msg))?
+        }
+        let dims: Vec<usize> = shape.dims().iter().map(|d| *d as usize).collect();
+        if size_in_bytes == 0 {
+            return Self::create_from_shape_and_untyped_data(shape.ty(), &dims, &[]);
+        }
+        // Safety: the mapping is dropped before returning and the file is expected not to be
+        // modified in the meantime.
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+        Self::create_from_shape_and_untyped_data(shape.ty(), &dims, &mmap)
+    }
+
     /// Get the first element from a literal. This returns an error if type `T` is not the
     /// primitive type that the literal uses.
     pub fn get_first_element<T: NativeType + ArrayElement>(&self) -> Result<T> {
@@ -150,6 +180,15 @@ impl Literal {
         Ok(())
     }
 
+    /// Copy the raw bytes of the literal data in a newly created vector.
+    pub(super) fn untyped_data(&self) -> Vec<u8> {
+        let mut data = vec![0u8; self.size_bytes()];
+        unsafe {
+            c_lib::literal_copy_to(self.0, data.as_mut_ptr() as *mut libc::c_void, data.len())
+        };
+        data
+    }
+
     /// Copy the values stored in the literal in a newly created vector. The data is flattened out
     /// for literals with more than one dimension.
     pub fn to_vec<T: ArrayElement>(&self) -> Result<Vec<T>> {
@@ -174,6 +213,21 @@ impl Literal {
         Literal(ptr)
     }
 
+    /// Create a literal with the specified dimensions where all the elements are set to `value`.
+    /// This happens on the host, no device computation is involved. This returns an error if
+    /// some of the dimensions are negative.
+    pub fn broadcast_scalar<T: NativeType + ArrayElement>(value: T, dims: &[i64]) -> Result<Self> {
+        if dims.iter().any(|d| *d < 0) {
+            let msg = format!("broadcast_scalar: negative dimension in {dims:?}");
+            Err(super::xla_error(msg))?
+        }
+        let dims: Vec<_> = dims.iter().map(|d| *d as usize).collect();
+        let element_count = dims.iter().product::<usize>();
+        let mut literal = Self::create_from_shape(T::TY.primitive_type(), &dims);
+        literal.copy_raw_from(&vec![value; element_count])?;
+        Ok(literal)
+    }
+
     /// Create a new literal containing the same data but using a different shape. This returns an
     /// error if the number of elements in the literal is different from the product of the target
     /// dimension sizes.
@@ -185,6 +239,31 @@ impl Literal {
         Ok(Literal(result))
     }
 
+    /// Iterate over the sub-literals obtained by indexing the first dimension, e.g. a literal of
+    /// shape `[3, 2]` results in three literals of shape `[2]`. The data is copied once when
+    /// creating the iterator, a single error is produced if the literal has no dimensions.
+    pub fn iter_leading(&self) -> impl Iterator<Item = Result<Literal>> {
+        let data = self.array_shape().and_then(|shape| {
+            if shape.dims().is_empty() {
+                Err(Error::UnexpectedNumberOfDims { expected: 1, got: 0, dims: vec![] })?
+            }
+            Ok((shape, self.untyped_data()))
+        });
+        let (data, err) = match data {
+            Ok(data) => (Some(data), None),
+            Err(err) => (None, Some(Err(err))),
+        };
+        err.into_iter().chain(data.into_iter().flat_map(|(shape, data)| {
+            let first_dim = shape.dims()[0] as usize;
+            let sub_dims: Vec<_> = shape.dims()[1..].iter().map(|d| *d as usize).collect();
+            let chunk_len = if first_dim == 0 { 0 } else { data.len() / first_dim };
+            (0..first_dim).map(move |i| {
+                let chunk = &data[i * chunk_len..(i + 1) * chunk_len];
+                Self::create_from_shape_and_untyped_data(shape.ty(), &sub_dims, chunk)
+            })
+        }))
+    }
+
     /// Create a new literal containing the data from the original literal casted to a new
     /// primitive type. The dimensions of the resulting literal are the same as the dimensions of
     /// the original literal.
@@ -263,6 +342,78 @@ impl Literal {
         drop(elems);
         Self(literal)
     }
+
+    /// Check that two literals have the same dimensions and that their elements are close, i.e.
+    /// `|actual - expected| <= atol + rtol * |expected|` as in `numpy.testing.assert_allclose`.
+    /// Two `NaN` values are considered equal, as are two infinite values with the same sign.
+    /// Complex literals are not supported. On failure, the returned error lists the first
+    /// mismatching indexes together with the actual and expected values.
+    pub fn assert_close(&self, expected: &Literal, rtol: f64, atol: f64) -> Result<()> {
+        const MAX_REPORTED_MISMATCHES: usize = 5;
+        for literal in [self, expected] {
+            let shape = literal.array_shape()?;
+            if let ElementType::C64 | ElementType::C128 = shape.ty() {
+                Err(Error::UnsupportedElementType {
+                    ty: shape.primitive_type(),
+                    op: "assert_close",
+                })?
+            }
+        }
+        let dims = self.array_shape()?.dims().to_vec();
+        let expected_dims = expected.array_shape()?.dims().to_vec();
+        if dims != expected_dims {
+            let msg = format!("shape mismatch, actual: {dims:?}, expected: {expected_dims:?}");
+            Err(super::xla_error(msg))?
+        }
+        let actual = self.convert(PrimitiveType::F64)?.to_vec::<f64>()?;
+        let expected = expected.convert(PrimitiveType::F64)?.to_vec::<f64>()?;
+        let mismatches: Vec<_> = actual
+            .iter()
+            .zip(expected.iter())
+            .enumerate()
+            .filter(|(_, (a, e))| {
+                let both_nan = a.is_nan() && e.is_nan();
+                let is_close = a == e || (*a - *e).abs() <= atol + rtol * e.abs();
+                !both_nan && !is_close
+            })
+            .collect();
+        if mismatches.is_empty() {
+            return Ok(());
+        }
+        let mut msg = format!(
+            "literals are not close (rtol: {rtol}, atol: {atol}), {} / {} elements mismatch",
+            mismatches.len(),
+            actual.len()
+        );
+        for (flat_index, (a, e)) in mismatches.into_iter().take(MAX_REPORTED_MISMATCHES) {
+            let mut index = vec![0; dims.len()];
+            let mut rem = flat_index as i64;
+            for (i, d) in dims.iter().enumerate().rev() {
+                index[i] = rem % d;
+                rem /= d;
+            }
+            msg.push_str(&format!("\n  {index:?}: actual {a}, expected {e}"));
+        }
+        Err(super::xla_error(msg))
+    }
+
+    /// Count the occurrences of each value in `0..num_bins` for an integer literal, the element
+    /// at index `i` of the result is the number of elements equal to `i`. Values outside of this
+    /// range, including negative values, are ignored.
+    pub fn bincount(&self, num_bins: usize) -> Result<Vec<u64>> {
+        use ElementType::*;
+        let ty = self.ty()?;
+        if !matches!(ty, S8 | S16 | S32 | S64 | U8 | U16 | U32 | U64) {
+            Err(Error::UnsupportedElementType { ty: ty.primitive_type(), op: "bincount" })?
+        }
+        let mut counts = vec![0u64; num_bins];
+        for v in self.convert(PrimitiveType::S64)?.to_vec::<i64>()? {
+            if let Some(count) = usize::try_from(v).ok().and_then(|v| counts.get_mut(v)) {
+                *count += 1
+            }
+        }
+        Ok(counts)
+    }
 }
 
 impl<T: NativeType> From<T> for Literal {
@@ -1,9 +1,27 @@
+#[cfg(feature = "arrow")]
+mod arrow_interop;
+mod autodiff;
+mod builder_ops;
+mod checked_arithmetic;
+mod comparison_ops;
+mod composite;
+mod eval;
+mod indexing_ops;
+mod linalg_ops;
 mod literal;
+mod nn_ops;
+mod parameter_map;
 mod pjrt_buffer;
 mod pjrt_client;
 mod pjrt_device;
 mod pjrt_loaded_executable;
+mod scalar_computations;
 mod shape;
+mod shape_ops;
+mod sharding;
+mod signature;
+mod testing;
+mod type_promotion;
 mod xla_builder;
 mod xla_op;
 
@@ -12,12 +30,23 @@ use crate::error::{Error, Result};
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 
+pub use autodiff::{VjpBuilder, VjpVar};
+pub use comparison_ops::{CompareDirection, ComparisonType};
+pub use composite::{CompositeBuilder, CompositeRegistry};
+pub use eval::eval_multi;
 pub use literal::Literal;
+pub use nn_ops::Activation;
+pub use parameter_map::{DeferredConstant, ParameterMap};
 pub use pjrt_buffer::PjRtBuffer;
 pub use pjrt_client::PjRtClient;
-pub use pjrt_device::PjRtDevice;
+pub use pjrt_device::{OutfeedReader, PjRtDevice};
 pub use pjrt_loaded_executable::PjRtLoadedExecutable;
+pub use scalar_computations::ScalarComputations;
 pub use shape::{ArrayShape, Shape};
+pub use shape_ops::DataFormat;
+pub use sharding::{DeviceMesh, OpSharding};
+pub use signature::Signature;
+pub use testing::finite_difference_jacobian;
 pub use xla_builder::XlaBuilder;
 pub use xla_op::XlaOp;
 
@@ -77,7 +106,7 @@ impl PrimitiveType {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ElementType {
     Pred,
     S8,
@@ -292,6 +321,12 @@ element_type!(f64, F64, 8);
 /// specialized to a given device through a compilation step.
 pub struct XlaComputation(c_lib::xla_computation);
 
+/// An [`Error::XlaError`] for errors detected on the Rust side rather than reported by XLA.
+pub(super) fn xla_error(msg: String) -> Error {
+    let backtrace = std::backtrace::Backtrace::capture().to_string();
+    Error::XlaError { msg, backtrace }
+}
+
 fn handle_status(status: c_lib::status) -> Result<()> {
     if status.is_null() {
         Ok(())
@@ -302,8 +337,7 @@ fn handle_status(status: c_lib::status) -> Result<()> {
             c_lib::status_free(status);
             error_message
         };
-        let backtrace = std::backtrace::Backtrace::capture().to_string();
-        Err(Error::XlaError { msg, backtrace })
+        Err(xla_error(msg))
     }
 }
 
@@ -0,0 +1,241 @@
+//! Neural network building blocks, these are composite ops that get expanded to basic XLA ops
+//! when built.
+use super::XlaOp;
+use crate::{Error, Result};
+
+/// The activation functions that can be applied after a linear layer, see [`XlaOp::linear`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Activation {
+    Relu,
+    Gelu,
+    Sigmoid,
+    Silu,
+    Tanh,
+}
+
+impl Activation {
+    /// Apply the activation function to an op.
+    pub fn apply(&self, xs: &XlaOp) -> Result<XlaOp> {
+        match self {
+            Self::Relu => xs.relu(),
+            Self::Gelu => xs.gelu(),
+            Self::Sigmoid => xs.sigmoid(),
+            Self::Silu => xs.silu(),
+            Self::Tanh => xs.tanh(),
+        }
+    }
+}
+
+impl XlaOp {
+    /// Scaled dot-product attention, `softmax(q.kᵀ * scale + mask).v` where `self` is the query.
+    /// The query and key have shape `[.., seq_len, head_dim]`, the value has shape
+    /// `[.., kv_seq_len, v_dim]`. The optional mask is added to the attention scores and has to
+    /// broadcast to `[.., seq_len, kv_seq_len]`, e.g. using `-inf` for masked positions. `scale`
+    /// defaults to `1 / sqrt(head_dim)`.
+    pub fn scaled_dot_product_attention(
+        &self,
+        key: &XlaOp,
+        value: &XlaOp,
+        mask: Option<&XlaOp>,
+        scale: Option<f64>,
+    ) -> Result<Self> {
+        let b = self.builder();
+        let ty = self.ty()?;
+        let scale = match scale {
+            Some(scale) => scale,
+            None => {
+                let head_dim = self.array_shape()?.last_dim().unwrap_or(1);
+                1. / (head_dim as f64).sqrt()
+            }
+        };
+        let scale = b.c0(scale)?.convert(ty)?;
+        let att = (self.matmul(&key.swap_dims(-2, -1)?)? * scale)?;
+        let att = match mask {
+            None => att,
+            Some(mask) => {
+                let (att, mask) = att.broadcast_with(mask)?;
+                (att + mask)?
+            }
+        };
+        att.softmax(-1)?.matmul(value)
+    }
+
+    /// Compute the mean and (biased) variance of the op over the specified dimensions. Both
+    /// statistics are obtained in a single pass, as `E[x²] - E[x]²` using the sum and the sum of
+    /// squares, these two reductions over the same input get fused by XLA. This can lose
+    /// precision to cancellation when the mean is large compared to the standard deviation, the
+    /// variance is clamped to be non-negative.
+    pub fn moments(&self, dims: &[i64], keep_dims: bool) -> Result<(XlaOp, XlaOp)> {
+        let b = self.builder();
+        let shape = self.array_shape()?;
+        let rank = shape.dims().len();
+        let mut element_count = 1i64;
+        for &dim in dims.iter() {
+            let d = if dim < 0 { dim + rank as i64 } else { dim };
+            if d < 0 || d >= rank as i64 {
+                Err(Error::IndexOutOfBounds { index: dim, rank })?
+            }
+            element_count *= shape.dims()[d as usize];
+        }
+        let ty = self.ty()?;
+        let element_count = b.c0(element_count as f64)?.convert(ty)?;
+        let mean = (self.reduce_sum(dims, keep_dims)? / &element_count)?;
+        let mean_sq = ((self * self)?.reduce_sum(dims, keep_dims)? / element_count)?;
+        let var = (mean_sq - (&mean * &mean)?)?.max(&b.zero(shape.ty())?)?;
+        Ok((mean, var))
+    }
+
+    /// Local response normalization as used in AlexNet, the normalization happens over the last
+    /// dimension which is expected to be the channel one.
+    /// `sqr_sum[.., c] = sum(x[.., c - depth_radius..=c + depth_radius] ** 2)` and the output is
+    /// `x / (bias + alpha * sqr_sum) ** beta`, matching TensorFlow `local_response_normalization`.
+    pub fn local_response_norm(
+        &self,
+        depth_radius: i64,
+        bias: f64,
+        alpha: f64,
+        beta: f64,
+    ) -> Result<Self> {
+        let b = self.builder();
+        let ty = self.ty()?;
+        let shape = self.array_shape()?;
+        let channels = match shape.last_dim() {
+            None => Err(Error::UnexpectedNumberOfDims { expected: 1, got: 0, dims: vec![] })?,
+            Some(channels) => channels,
+        };
+        let channel_dim = shape.dims().len() as i64 - 1;
+        let zero = b.c0(0f32)?.convert(ty)?;
+        let sqr = (self * self)?.pad_in_dim(&zero, channel_dim, depth_radius, depth_radius)?;
+        // Sum the squares over the channel window by adding shifted slices of the padded op.
+        let mut sqr_sum = sqr.slice_in_dim1(0, channels, -1)?;
+        for offset in 1..=2 * depth_radius {
+            sqr_sum = (sqr_sum + sqr.slice_in_dim1(offset, offset + channels, -1)?)?;
+        }
+        let bias = b.c0(bias)?.convert(ty)?;
+        let alpha = b.c0(alpha)?.convert(ty)?;
+        let beta = b.c0(beta)?.convert(ty)?;
+        let denominator = ((alpha * sqr_sum)? + bias)?.pow(&beta)?;
+        self / denominator
+    }
+
+    /// Simulate the uniform affine quantization of the op to `num_bits` bits over the range
+    /// `[min, max]`, the output stays in floating point. As in TensorFlow
+    /// `fake_quant_with_min_max_args`, the range is first nudged so that zero is exactly
+    /// representable, values are then clamped to the nudged range and rounded to the nearest
+    /// point of the quantization grid. The integer range is `[0, 2^num_bits - 1]`, or
+    /// `[1, 2^num_bits - 1]` if `narrow_range` is true. `num_bits` has to be between 1 and 32,
+    /// or between 2 and 32 when `narrow_range` is true, and `min` has to be smaller than `max`.
+    pub fn fake_quant(
+        &self,
+        num_bits: i64,
+        min: f64,
+        max: f64,
+        narrow_range: bool,
+    ) -> Result<Self> {
+        let min_bits = if narrow_range { 2 } else { 1 };
+        if !(min_bits..=32).contains(&num_bits) {
+            Err(super::xla_error(format!(
+                "fake_quant: num_bits has to be between {min_bits} and 32, got {num_bits}"
+            )))?
+        }
+        let is_valid_range = min < max && min.is_finite() && max.is_finite();
+        if !is_valid_range {
+            Err(super::xla_error(format!("fake_quant: invalid range [{min}, {max}]")))?
+        }
+        let b = self.builder();
+        let ty = self.ty()?;
+        let quant_min = if narrow_range { 1. } else { 0. };
+        let quant_max = ((1i64 << num_bits) - 1) as f64;
+        let scale = (max - min) / (quant_max - quant_min);
+        let zero_point = (quant_min - min / scale).round().clamp(quant_min, quant_max);
+        let nudged_min = (quant_min - zero_point) * scale;
+        let nudged_max = (quant_max - zero_point) * scale;
+        let c = |v: f64| b.c0(v)?.convert(ty);
+        let clamped = self.max(&c(nudged_min)?)?.min(&c(nudged_max)?)?;
+        let quantized = (((clamped - c(nudged_min)?)? / c(scale)?)? + c(0.5)?)?.floor()?;
+        (quantized * c(scale)?)? + c(nudged_min)?
+    }
+
+    /// A dense layer, `activation(self . weight + bias)`. `self` has shape `[.., in_dim]`,
+    /// `weight` has shape `[in_dim, out_dim]` and the optional `bias` has shape `[out_dim]`, it
+    /// is broadcasted over the leading dimensions. The result has shape `[.., out_dim]`.
+    pub fn linear(
+        &self,
+        weight: &XlaOp,
+        bias: Option<&XlaOp>,
+        activation: Option<Activation>,
+    ) -> Result<Self> {
+        let rank = self.rank()?;
+        let weight_dims = weight.array_shape()?.dims().to_vec();
+        if rank == 0 || weight_dims.len() != 2 {
+            Err(Error::MatMulIncorrectDims {
+                lhs_dims: self.array_shape()?.dims().to_vec(),
+                rhs_dims: weight_dims,
+                msg: "linear expects a weight of rank 2 and an input of rank at least 1",
+            })?
+        }
+        let ys = self.dot_general(weight, &[rank as i64 - 1], &[0], &[], &[])?;
+        let ys = match bias {
+            None => ys,
+            Some(bias) => {
+                let out_dims = ys.array_shape()?.dims().to_vec();
+                (&ys + bias.broadcast_in_dim(&out_dims, &[rank as i64 - 1])?)?
+            }
+        };
+        match activation {
+            None => Ok(ys),
+            Some(activation) => activation.apply(&ys),
+        }
+    }
+
+    /// Smoothly clamp the values of the op to `[lo, hi]`, this computes
+    /// `lo + softplus(x - lo) - softplus(x - hi)` where softplus is scaled by `beta`, i.e.
+    /// `softplus(z) = log(1 + exp(beta * z)) / beta`.
+    /// Values well inside the range are left almost unchanged while values far outside of it get
+    /// close to the bounds, the gradient is non-zero everywhere. Larger values of `beta` result
+    /// in a sharper transition, the hard clamp being obtained as `beta` goes to infinity.
+    pub fn soft_clip(&self, lo: f64, hi: f64, beta: f64) -> Result<Self> {
+        let b = self.builder();
+        let ty = self.ty()?;
+        let c = |v: f64| b.c0(v)?.convert(ty);
+        // Use the numerically stable form `log(1 + exp(z)) = max(z, 0) + log(1 + exp(-|z|))`.
+        let softplus = |xs: &XlaOp| -> Result<XlaOp> {
+            let zs = (xs * c(beta)?)?;
+            let exp = (zs.abs()? * c(-1.)?)?.exp()?;
+            let zs = (zs.max(&c(0.)?)? + (exp + c(1.)?)?.log()?)?;
+            zs / c(beta)?
+        };
+        let lower = softplus(&(self - c(lo)?)?)?;
+        let upper = softplus(&(self - c(hi)?)?)?;
+        (lower - upper)? + c(lo)?
+    }
+
+    /// Add a per-channel `bias` of shape `[channels]` to the op, the bias is broadcasted along
+    /// all the dimensions except `feature_dim` which must have size `channels`. For a convolution
+    /// output `feature_dim` is 1 with the NCHW layout and -1 (or 3) with the NHWC layout.
+    pub fn add_bias(&self, bias: &XlaOp, feature_dim: i64) -> Result<Self> {
+        let shape = self.array_shape()?;
+        let dims = shape.dims();
+        let rank = dims.len() as i64;
+        let dim = if feature_dim < 0 { feature_dim + rank } else { feature_dim };
+        if dim < 0 || dim >= rank {
+            Err(Error::IndexOutOfBounds { index: feature_dim, rank: dims.len() })?
+        }
+        let bias_shape = bias.array_shape()?;
+        let bias_dims = bias_shape.dims();
+        if bias_dims.len() != 1 {
+            Err(Error::UnexpectedNumberOfDims {
+                expected: 1,
+                got: bias_dims.len(),
+                dims: bias_dims.to_vec(),
+            })?
+        }
+        if bias_dims[0] != dims[dim as usize] {
+            Err(Error::WrongElementCount {
+                dims: dims.iter().map(|d| *d as usize).collect(),
+                element_count: bias_dims[0] as usize,
+            })?
+        }
+        self + bias.broadcast_in_dim(dims, &[dim])?
+    }
+}
@@ -0,0 +1,162 @@
+//! Name based binding of computation parameters.
+use super::{ElementType, Literal, PjRtBuffer, PjRtClient, Shape, Signature, XlaBuilder, XlaOp};
+use crate::Result;
+use std::collections::HashMap;
+
+/// Keeps track of the names of the parameters of a computation so that the arguments can be
+/// bound by name rather than by position when executing it, see
+/// [`super::PjRtLoadedExecutable::execute_named`]. Parameters are numbered in the order in
+/// which they are created through the map.
+#[derive(Clone, Debug, Default)]
+pub struct ParameterMap {
+    names: Vec<String>,
+    indexes: HashMap<String, usize>,
+    shapes: Vec<Shape>,
+    constants: Vec<DeferredConstant>,
+}
+
+/// A large constant that is passed to the computation as a parameter rather than being embedded
+/// in it, see [`ParameterMap::large_constant`].
+#[derive(Clone)]
+pub struct DeferredConstant {
+    name: String,
+    literal: Literal,
+}
+
+impl DeferredConstant {
+    /// The name of the parameter holding the constant.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value of the constant.
+    pub fn literal(&self) -> &Literal {
+        &self.literal
+    }
+}
+
+impl std::fmt::Debug for DeferredConstant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let shape = self.literal.shape();
+        f.debug_struct("DeferredConstant").field("name", &self.name).field("shape", &shape).finish()
+    }
+}
+
+impl ParameterMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn register(&mut self, name: &str, shape: Shape) -> Result<i64> {
+        if self.indexes.contains_key(name) {
+            let msg = format!("parameter {name} has already been registered");
+            Err(super::xla_error(msg))?
+        }
+        let index = self.names.len();
+        self.names.push(name.to_string());
+        self.shapes.push(shape);
+        self.indexes.insert(name.to_string(), index);
+        Ok(index as i64)
+    }
+
+    /// Create a new named parameter using the next available parameter number.
+    pub fn parameter(
+        &mut self,
+        builder: &XlaBuilder,
+        ty: ElementType,
+        dims: &[i64],
+        name: &str,
+    ) -> Result<XlaOp> {
+        let index = self.register(name, Shape::array_with_type(ty, dims.to_vec()))?;
+        builder.parameter(index, ty, dims, name)
+    }
+
+    /// Create a new named parameter with the specified shape using the next available parameter
+    /// number.
+    pub fn parameter_s(
+        &mut self,
+        builder: &XlaBuilder,
+        shape: &Shape,
+        name: &str,
+    ) -> Result<XlaOp> {
+        let index = self.register(name, shape.clone())?;
+        builder.parameter_s(index, shape, name)
+    }
+
+    /// Create an op holding the value of `literal`. Rather than embedding the literal in the
+    /// computation, which makes both the computation proto and the compilation time grow with
+    /// the literal size, a new named parameter is created and the literal is kept in the map.
+    /// The literal has to be uploaded with [`ParameterMap::upload_constants`] and the resulting
+    /// buffers passed as arguments when executing the computation.
+    pub fn large_constant(
+        &mut self,
+        builder: &XlaBuilder,
+        name: &str,
+        literal: Literal,
+    ) -> Result<XlaOp> {
+        let shape = literal.shape()?;
+        let op = self.parameter_s(builder, &shape, name)?;
+        self.constants.push(DeferredConstant { name: name.to_string(), literal });
+        Ok(op)
+    }
+
+    /// The constants created with [`ParameterMap::large_constant`].
+    pub fn constants(&self) -> &[DeferredConstant] {
+        &self.constants
+    }
+
+    /// Transfer the constants created with [`ParameterMap::large_constant`] to `client`, the
+    /// resulting buffers are indexed by parameter name. This only has to be done once, the
+    /// buffers can then be reused for all the executions of the computation.
+    pub fn upload_constants(&self, client: &PjRtClient) -> Result<HashMap<String, PjRtBuffer>> {
+        self.constants
+            .iter()
+            .map(|c| {
+                let buffer = client.buffer_from_host_literal(None, &c.literal)?;
+                Ok((c.name.clone(), buffer))
+            })
+            .collect()
+    }
+
+    /// The signature of a computation with these parameters and `result` as its root op.
+    pub fn signature(&self, result: &XlaOp) -> Result<Signature> {
+        Ok(Signature::new(self.shapes.clone(), result.shape()?))
+    }
+
+    /// The parameter number associated with a name.
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.indexes.get(name).copied()
+    }
+
+    /// The parameter names, ordered by parameter number.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Reorder named arguments by parameter number. This returns an error if an argument does
+    /// not match any parameter or if a parameter has no associated argument.
+    pub fn bind<'a, T>(&self, args: &HashMap<&str, &'a T>) -> Result<Vec<&'a T>> {
+        if let Some(name) = args.keys().find(|name| !self.indexes.contains_key(**name)) {
+            let msg = format!("unknown parameter {name}, expected one of {:?}", self.names);
+            Err(super::xla_error(msg))?
+        }
+        self.names
+            .iter()
+            .map(|name| match args.get(name.as_str()) {
+                Some(arg) => Ok(*arg),
+                None => {
+                    let msg = format!("missing argument for parameter {name}");
+                    Err(super::xla_error(msg))
+                }
+            })
+            .collect()
+    }
+}
@@ -1,14 +1,38 @@
 //! A view on a memory slice hosted on a device.
-use super::{ArrayElement, ArrayShape, Literal, PjRtDevice, Shape};
+use super::{ArrayElement, ArrayShape, Literal, PjRtDevice, Shape, XlaBuilder};
 use crate::{c_lib, Error, Result};
 
 /// A buffer represents a view on a memory slice hosted on a device.
 pub struct PjRtBuffer {
     pub(super) buffer: c_lib::pjrt_buffer,
     pub(super) client: super::PjRtClient,
+    size_in_bytes: usize,
+    on_default_device: bool,
+}
+
+fn size_in_bytes(shape: &Shape) -> usize {
+    match shape {
+        Shape::Array(a) => a.element_count() * a.ty().element_size_in_bytes(),
+        Shape::Tuple(shapes) => shapes.iter().map(size_in_bytes).sum(),
+        Shape::Unsupported(_) => 0,
+    }
 }
 
 impl PjRtBuffer {
+    /// Wrap a buffer created by `client`, the buffer is tracked in the client live buffers until
+    /// it gets dropped. `on_default_device` records whether the buffer is stored on the default
+    /// device of the client.
+    pub(super) fn new(
+        buffer: c_lib::pjrt_buffer,
+        client: super::PjRtClient,
+        on_default_device: bool,
+    ) -> Self {
+        let mut buffer = Self { buffer, client, size_in_bytes: 0, on_default_device };
+        buffer.size_in_bytes = buffer.on_device_shape().map_or(0, |s| size_in_bytes(&s));
+        buffer.client.register_buffer(buffer.size_in_bytes);
+        buffer
+    }
+
     /// The client that owns this buffer.
     pub fn client(&self) -> &super::PjRtClient {
         &self.client
@@ -20,7 +44,8 @@ impl PjRtBuffer {
         let status =
             unsafe { c_lib::pjrt_buffer_copy_to_device(self.buffer, device.device, &mut buffer) };
         super::handle_status(status)?;
-        Ok(Self { buffer, client: self.client.clone() })
+        let on_default_device = self.client.is_default_device(device.device);
+        Ok(Self::new(buffer, self.client.clone(), on_default_device))
     }
 
     /// Copy the buffer back to the host as a literal.
@@ -38,6 +63,33 @@ impl PjRtBuffer {
         c_shape.shape()
     }
 
+    /// Create a new buffer with the same elements as this one but with dimensions `dims`, the
+    /// number of elements has to be preserved. The data does not go through the host, instead a
+    /// small computation performing the reshape is compiled and run on the default device of the
+    /// client, so this should not be used in a hot loop. An error is returned if this buffer is
+    /// not stored on the default device, such buffers can be copied there with
+    /// [`PjRtBuffer::copy_to_device`] first.
+    pub fn reshape(&self, dims: &[i64]) -> Result<PjRtBuffer> {
+        if !self.on_default_device {
+            Err(super::xla_error(
+                "reshape only supports buffers on the default device".to_string(),
+            ))?
+        }
+        let shape = ArrayShape::try_from(&self.on_device_shape()?)?;
+        let element_count: i64 = dims.iter().product();
+        if element_count as usize != shape.element_count() {
+            Err(Error::WrongElementCount {
+                dims: dims.iter().map(|d| *d as usize).collect(),
+                element_count: shape.element_count(),
+            })?
+        }
+        let builder = XlaBuilder::new("reshape");
+        let x = builder.parameter(0, shape.ty(), shape.dims(), "x")?;
+        let exe = x.reshape(dims)?.build()?.compile(&self.client)?;
+        let mut outputs = exe.execute_b(&[self])?;
+        Ok(outputs.remove(0).remove(0))
+    }
+
     /// Copy the data stored in a buffer to host memory in a blocking way.
     pub fn copy_raw_to_host_sync<T: ArrayElement>(
         &self,
@@ -68,6 +120,7 @@ impl PjRtBuffer {
 
 impl Drop for PjRtBuffer {
     fn drop(&mut self) {
+        self.client.unregister_buffer(self.size_in_bytes);
         unsafe { c_lib::pjrt_buffer_free(self.buffer) }
     }
 }
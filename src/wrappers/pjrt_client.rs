@@ -1,10 +1,21 @@
 //! A device (CPUs, GPUs, TPUs) where computations can be run.
-use super::{ArrayElement, Literal, PjRtBuffer, PjRtDevice, PjRtLoadedExecutable, XlaComputation};
+use super::{
+    ArrayElement, ArrayShape, Literal, OutfeedReader, PjRtBuffer, PjRtDevice, PjRtLoadedExecutable,
+    Shape, XlaComputation,
+};
 use crate::{c_lib, Error, Result};
+use std::cell::Cell;
 use std::marker::PhantomData;
 use std::rc::Rc;
 
-pub(super) struct PjRtClientInternal(pub(self) c_lib::pjrt_client);
+/// The number and total size of the buffers created by a client that have not been dropped yet.
+#[derive(Default)]
+struct LiveBuffers {
+    count: Cell<usize>,
+    bytes: Cell<usize>,
+}
+
+pub(super) struct PjRtClientInternal(pub(self) c_lib::pjrt_client, LiveBuffers);
 
 /// A client represents a device that can be used to run some computations. A computation graph is
 /// compiled in a way that is specific to a device before it can be run.
@@ -17,7 +28,7 @@ impl PjRtClient {
         let mut ptr: c_lib::pjrt_client = std::ptr::null_mut();
         let status = unsafe { c_lib::pjrt_cpu_client_create(&mut ptr) };
         super::handle_status(status)?;
-        Ok(Self(Rc::new(PjRtClientInternal(ptr))))
+        Ok(Self(Rc::new(PjRtClientInternal(ptr, LiveBuffers::default()))))
     }
 
     /// A GPU client, the memory requirements are limited by the specified `memory_fraction` and
@@ -28,7 +39,7 @@ impl PjRtClient {
         let status =
             unsafe { c_lib::pjrt_gpu_client_create(&mut ptr, memory_fraction, preallocate) };
         super::handle_status(status)?;
-        Ok(Self(Rc::new(PjRtClientInternal(ptr))))
+        Ok(Self(Rc::new(PjRtClientInternal(ptr, LiveBuffers::default()))))
     }
 
     /*
@@ -38,7 +49,7 @@ impl PjRtClient {
         let status =
             unsafe { c_lib::pjrt_tpu_client_create(&mut ptr, max_inflight_computations as i32) };
         super::handle_status(status)?;
-        Ok(Self(Rc::new(PjRtClientInternal(ptr))))
+        Ok(Self(Rc::new(PjRtClientInternal(ptr, LiveBuffers::default()))))
     }
     */
 
@@ -64,6 +75,56 @@ impl PjRtClient {
         unsafe { c_lib::pjrt_client_addressable_device_count(self.ptr()) as usize }
     }
 
+    /// The number of devices across all the processes of a multi-host setup, this is the same as
+    /// `device_count`. For single process backends, this is the same as
+    /// `addressable_device_count`.
+    pub fn global_device_count(&self) -> usize {
+        self.device_count()
+    }
+
+    /// The index of the current process in a multi-host setup, 0 for single process backends.
+    pub fn process_index(&self) -> usize {
+        self.addressable_devices().first().map_or(0, |d| d.process_index())
+    }
+
+    /// The number of processes in a multi-host setup, 1 for single process backends.
+    pub fn process_count(&self) -> usize {
+        let process_indexes: std::collections::HashSet<_> =
+            self.devices().iter().map(|d| d.process_index()).collect();
+        usize::max(process_indexes.len(), 1)
+    }
+
+    /// The number of buffers created through this client, either by transfers from the host or
+    /// as computation outputs, that have not been dropped yet. This can be used to detect
+    /// buffer leaks in long running processes.
+    pub fn live_buffer_count(&self) -> usize {
+        self.0 .1.count.get()
+    }
+
+    /// The total on-device size in bytes of the buffers counted by
+    /// [`PjRtClient::live_buffer_count`].
+    pub fn live_buffer_bytes(&self) -> usize {
+        self.0 .1.bytes.get()
+    }
+
+    pub(super) fn register_buffer(&self, size_in_bytes: usize) {
+        let live = &self.0 .1;
+        live.count.set(live.count.get() + 1);
+        live.bytes.set(live.bytes.get() + size_in_bytes);
+    }
+
+    pub(super) fn unregister_buffer(&self, size_in_bytes: usize) {
+        let live = &self.0 .1;
+        live.count.set(live.count.get() - 1);
+        live.bytes.set(live.bytes.get() - size_in_bytes);
+    }
+
+    /// Whether `device` is the device used when no device is specified, a null pointer stands
+    /// for this default device.
+    pub(super) fn is_default_device(&self, device: c_lib::pjrt_device) -> bool {
+        device.is_null() || self.addressable_devices().first().is_some_and(|d| d.device == device)
+    }
+
     /// The name of the platform.
     pub fn platform_name(&self) -> String {
         unsafe {
@@ -96,6 +157,20 @@ impl PjRtClient {
         device_ptrs.into_iter().map(|device| PjRtDevice { device, marker: PhantomData }).collect()
     }
 
+    /// Create a reader for the values that computations send to the outfeed of `device`. Each
+    /// call to [`OutfeedReader::read`] blocks until a value of shape `shape` is available, this
+    /// can be used to stream values out of a long running computation, e.g. from the body of a
+    /// while loop. Only array shapes are supported, an error is returned for tuple shapes.
+    pub fn outfeed_reader<'a>(
+        &self,
+        device: &PjRtDevice<'a>,
+        shape: &Shape,
+    ) -> Result<OutfeedReader<'a>> {
+        ArrayShape::try_from(shape)?;
+        let device = PjRtDevice { device: device.device, marker: PhantomData };
+        Ok(OutfeedReader { device, shape: shape.clone() })
+    }
+
     /// Transfer some data from the host to a `PjRtBuffer` stored on the target device. If the
     /// device is not specified, the default device is used.
     /// The source data is passed as a slice of the specified primitive type, as well as the
@@ -126,7 +201,8 @@ impl PjRtClient {
             )
         };
         super::handle_status(status)?;
-        Ok(PjRtBuffer { buffer, client: self.clone() })
+        let on_default_device = self.is_default_device(device);
+        Ok(PjRtBuffer::new(buffer, self.clone(), on_default_device))
     }
 
     /// Transfer some data from the host to a `PjRtBuffer` stored on the target device. If the
@@ -161,7 +237,8 @@ impl PjRtClient {
             )
         };
         super::handle_status(status)?;
-        Ok(PjRtBuffer { buffer, client: self.clone() })
+        let on_default_device = self.is_default_device(device);
+        Ok(PjRtBuffer::new(buffer, self.clone(), on_default_device))
     }
 
     /// Transfer some data from the host to a `PjRtBuffer` stored on the target device. If the
@@ -178,7 +255,8 @@ impl PjRtClient {
             c_lib::pjrt_buffer_from_host_literal(self.ptr(), device, literal.0, &mut buffer)
         };
         super::handle_status(status)?;
-        Ok(PjRtBuffer { buffer, client: self.clone() })
+        let on_default_device = self.is_default_device(device);
+        Ok(PjRtBuffer::new(buffer, self.clone(), on_default_device))
     }
 }
 
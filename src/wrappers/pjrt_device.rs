@@ -1,3 +1,4 @@
+use super::{ArrayShape, Literal, Shape};
 use crate::{c_lib, Result};
 use std::marker::PhantomData;
 
@@ -56,3 +57,27 @@ impl PjRtDevice<'_> {
         Ok(())
     }
 }
+
+/// Reads the values sent by a computation via the outfeed op of a device, see
+/// [`super::PjRtClient::outfeed_reader`].
+pub struct OutfeedReader<'a> {
+    pub(super) device: PjRtDevice<'a>,
+    pub(super) shape: Shape,
+}
+
+impl OutfeedReader<'_> {
+    /// The shape of the values read from the outfeed.
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /// Block until the device outfeed holds a value and return it. The value must have been
+    /// outfed with the shape specified when creating the reader.
+    pub fn read(&self) -> Result<Literal> {
+        let shape = ArrayShape::try_from(&self.shape)?;
+        let dims: Vec<_> = shape.dims().iter().map(|d| *d as usize).collect();
+        let mut literal = Literal::create_from_shape(shape.primitive_type(), &dims);
+        self.device.transfer_from_outfeed(&mut literal)?;
+        Ok(literal)
+    }
+}
@@ -1,5 +1,6 @@
-use super::{Literal, PjRtBuffer};
+use super::{Literal, ParameterMap, PjRtBuffer};
 use crate::{c_lib, Result};
+use std::collections::HashMap;
 
 pub struct PjRtLoadedExecutable {
     pub(super) exe: c_lib::pjrt_loaded_executable,
@@ -24,12 +25,15 @@ impl PjRtLoadedExecutable {
                     break;
                 }
                 let mut replica_vec = vec![];
+                // The first replica runs on the default device.
+                let on_default_device = vec.is_empty();
                 loop {
                     let buffer = *outputs.add(replica_vec.len());
                     if buffer.is_null() {
                         break;
                     }
-                    replica_vec.push(PjRtBuffer { buffer, client: self.client.clone() });
+                    let client = self.client.clone();
+                    replica_vec.push(PjRtBuffer::new(buffer, client, on_default_device));
                 }
                 libc::free(outputs as *mut libc::c_void);
                 vec.push(replica_vec);
@@ -62,6 +66,18 @@ impl PjRtLoadedExecutable {
         super::handle_status(status)?;
         Ok(self.process_execute_outputs(outputs))
     }
+
+    /// Execute the computation with arguments bound by name, `parameters` is used to map each
+    /// name to the corresponding parameter number. An error is returned if some parameters have
+    /// no argument or if some arguments do not correspond to any parameter.
+    pub fn execute_named(
+        &self,
+        parameters: &ParameterMap,
+        args: &HashMap<&str, &PjRtBuffer>,
+    ) -> Result<Vec<Vec<PjRtBuffer>>> {
+        let args = parameters.bind(args)?;
+        self.execute_b(&args)
+    }
 }
 
 impl Drop for PjRtLoadedExecutable {
@@ -0,0 +1,89 @@
+//! A cache for the scalar computations used as reducers.
+use super::{ElementType, XlaBuilder, XlaComputation, XlaOp};
+use crate::Result;
+use std::collections::hash_map::{Entry, HashMap};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum ScalarOp {
+    Add,
+    Mul,
+    Max,
+    Min,
+}
+
+impl ScalarOp {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Mul => "mul",
+            Self::Max => "max",
+            Self::Min => "min",
+        }
+    }
+
+    fn apply(&self, lhs: &XlaOp, rhs: &XlaOp) -> Result<XlaOp> {
+        match self {
+            Self::Add => lhs + rhs,
+            Self::Mul => lhs * rhs,
+            Self::Max => lhs.max(rhs),
+            Self::Min => lhs.min(rhs),
+        }
+    }
+}
+
+/// Builds the two-parameter scalar computations used by reductions once per element type and
+/// hands out references to them, e.g. to pass to [`XlaOp::reduce`]. This only avoids building a
+/// new scalar builder for each reduction: the builder still imports a separate copy of the
+/// computation on every call, so the resulting HLO is not any smaller.
+#[derive(Default)]
+pub struct ScalarComputations {
+    computations: HashMap<(ScalarOp, ElementType), XlaComputation>,
+}
+
+impl ScalarComputations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get_or_build(&mut self, op: ScalarOp, ty: ElementType) -> Result<&XlaComputation> {
+        match self.computations.entry((op, ty)) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let b = XlaBuilder::new(&format!("scalar_{}_{ty:?}", op.name()));
+                let lhs = b.parameter(0, ty, &[], "lhs")?;
+                let rhs = b.parameter(1, ty, &[], "rhs")?;
+                let computation = op.apply(&lhs, &rhs)?.build()?;
+                Ok(entry.insert(computation))
+            }
+        }
+    }
+
+    /// The computation adding two scalars of type `ty`.
+    pub fn add(&mut self, ty: ElementType) -> Result<&XlaComputation> {
+        self.get_or_build(ScalarOp::Add, ty)
+    }
+
+    /// The computation multiplying two scalars of type `ty`.
+    pub fn mul(&mut self, ty: ElementType) -> Result<&XlaComputation> {
+        self.get_or_build(ScalarOp::Mul, ty)
+    }
+
+    /// The computation returning the maximum of two scalars of type `ty`.
+    pub fn max(&mut self, ty: ElementType) -> Result<&XlaComputation> {
+        self.get_or_build(ScalarOp::Max, ty)
+    }
+
+    /// The computation returning the minimum of two scalars of type `ty`.
+    pub fn min(&mut self, ty: ElementType) -> Result<&XlaComputation> {
+        self.get_or_build(ScalarOp::Min, ty)
+    }
+
+    /// The number of computations that have been built so far.
+    pub fn len(&self) -> usize {
+        self.computations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.computations.is_empty()
+    }
+}
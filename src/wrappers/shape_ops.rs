@@ -0,0 +1,343 @@
+//! Helpers rearranging the dimensions of an op, these are implemented on top of the basic
+//! reshape/transpose/slice primitives.
+use super::{ElementType, Literal, XlaOp};
+use crate::{Error, Result};
+use std::collections::HashMap;
+
+/// The memory layout used for the dimensions of image-like tensors.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DataFormat {
+    /// Batch, channels, height, width.
+    Nchw,
+    /// Batch, height, width, channels.
+    Nhwc,
+}
+
+/// Parse one side of a rearrange pattern, e.g. `b (h w) c`, into the groups of axis names of each
+/// dimension, here `[[b], [h, w], [c]]`.
+fn parse_rearrange_side(pattern: &str, side: &str) -> Result<Vec<Vec<String>>> {
+    let err = |msg: &str| super::xla_error(format!("invalid rearrange pattern {pattern:?}: {msg}"));
+    let mut groups = vec![];
+    let mut current_group: Option<Vec<String>> = None;
+    let side = side.replace('(', " ( ").replace(')', " ) ");
+    for token in side.split_whitespace() {
+        match token {
+            "(" if current_group.is_none() => current_group = Some(vec![]),
+            ")" if current_group.is_some() => groups.extend(current_group.take()),
+            "(" | ")" => Err(err("unbalanced parentheses"))?,
+            name if !name.chars().all(|c| c.is_alphanumeric() || c == '_') => {
+                Err(err(&format!("unexpected axis name {name}")))?
+            }
+            name => match current_group.as_mut() {
+                Some(group) => group.push(name.to_string()),
+                None => groups.push(vec![name.to_string()]),
+            },
+        }
+    }
+    if current_group.is_some() {
+        Err(err("unbalanced parentheses"))?
+    }
+    Ok(groups)
+}
+
+impl XlaOp {
+    /// View a complex tensor as a real one, a `C64` (resp. `C128`) op of shape `[..]` results in
+    /// a `F32` (resp. `F64`) op of shape `[.., 2]` where the last dimension holds the real and
+    /// imaginary parts. This matches PyTorch `view_as_real`.
+    pub fn view_as_real(&self) -> Result<Self> {
+        let shape = self.array_shape()?;
+        match shape.ty() {
+            ElementType::C64 | ElementType::C128 => {}
+            _ => Err(Error::UnsupportedElementType {
+                ty: shape.primitive_type(),
+                op: "view_as_real",
+            })?,
+        }
+        let mut dims = shape.dims().to_vec();
+        dims.push(1);
+        let re = self.real()?.reshape(&dims)?;
+        let im = self.imag()?.reshape(&dims)?;
+        re.concat_in_dim(&[&im], -1)
+    }
+
+    /// The inverse of [`XlaOp::view_as_real`], a `F32` (resp. `F64`) op of shape `[.., 2]` is
+    /// converted to a `C64` (resp. `C128`) op of shape `[..]`. The last dimension must have
+    /// size 2. The imaginary part is multiplied by `i`, so non-finite imaginary parts result in
+    /// a `NaN` real part.
+    pub fn view_as_complex(&self) -> Result<Self> {
+        let shape = self.array_shape()?;
+        let imaginary_unit = match shape.ty() {
+            ElementType::F32 => {
+                let data: Vec<u8> = [0f32, 1f32].iter().flat_map(|v| v.to_ne_bytes()).collect();
+                Literal::create_from_shape_and_untyped_data(ElementType::C64, &[], &data)?
+            }
+            ElementType::F64 => {
+                let data: Vec<u8> = [0f64, 1f64].iter().flat_map(|v| v.to_ne_bytes()).collect();
+                Literal::create_from_shape_and_untyped_data(ElementType::C128, &[], &data)?
+            }
+            _ => Err(Error::UnsupportedElementType {
+                ty: shape.primitive_type(),
+                op: "view_as_complex",
+            })?,
+        };
+        let mut dims = shape.dims().to_vec();
+        match dims.pop() {
+            None => Err(Error::UnexpectedNumberOfDims { expected: 1, got: 0, dims: vec![] })?,
+            Some(2) => {}
+            Some(last_dim) => Err(super::xla_error(format!(
+                "view_as_complex: the last dimension has to be of size 2, got {last_dim}"
+            )))?,
+        }
+        let i = self.builder().constant_literal(&imaginary_unit)?;
+        let complex_ty = i.ty()?;
+        let re = self.slice_in_dim1(0, 1, -1)?.reshape(&dims)?.convert(complex_ty)?;
+        let im = self.slice_in_dim1(1, 2, -1)?.reshape(&dims)?.convert(complex_ty)?;
+        re + (im * i)?
+    }
+
+    /// Rearrange blocks of `block_size * block_size` channels into spatial blocks, the output
+    /// has `block_size` times the height and width of the input and `block_size * block_size`
+    /// times less channels. This uses the same depth-column-row ordering as TensorFlow
+    /// `depth_to_space`.
+    pub fn depth_to_space(&self, block_size: i64, data_format: DataFormat) -> Result<Self> {
+        let shape = self.array_shape()?;
+        let dims = <(i64, i64, i64, i64)>::try_from(&shape)?;
+        let bs = block_size;
+        let (n, c, h, w) = match data_format {
+            DataFormat::Nchw => dims,
+            DataFormat::Nhwc => (dims.0, dims.3, dims.1, dims.2),
+        };
+        if bs < 1 {
+            Err(super::xla_error(format!("depth_to_space: invalid block size {bs}")))?
+        }
+        if c % (bs * bs) != 0 {
+            Err(super::xla_error(format!(
+                "depth_to_space: {c} channels cannot be split in blocks of size {bs}x{bs}"
+            )))?
+        }
+        let c = c / (bs * bs);
+        match data_format {
+            DataFormat::Nchw => self
+                .reshape(&[n, bs, bs, c, h, w])?
+                .transpose(&[0, 3, 4, 1, 5, 2])?
+                .reshape(&[n, c, h * bs, w * bs]),
+            DataFormat::Nhwc => self
+                .reshape(&[n, h, w, bs, bs, c])?
+                .transpose(&[0, 1, 3, 2, 4, 5])?
+                .reshape(&[n, h * bs, w * bs, c]),
+        }
+    }
+
+    /// The inverse of [`XlaOp::depth_to_space`], spatial blocks of size
+    /// `block_size * block_size` are moved to the channel dimension.
+    pub fn space_to_depth(&self, block_size: i64, data_format: DataFormat) -> Result<Self> {
+        let shape = self.array_shape()?;
+        let dims = <(i64, i64, i64, i64)>::try_from(&shape)?;
+        let bs = block_size;
+        let (n, c, h, w) = match data_format {
+            DataFormat::Nchw => dims,
+            DataFormat::Nhwc => (dims.0, dims.3, dims.1, dims.2),
+        };
+        if bs < 1 {
+            Err(super::xla_error(format!("space_to_depth: invalid block size {bs}")))?
+        }
+        if h % bs != 0 || w % bs != 0 {
+            Err(super::xla_error(format!(
+                "space_to_depth: height {h} and width {w} have to be divisible by block size {bs}"
+            )))?
+        }
+        match data_format {
+            DataFormat::Nchw => self
+                .reshape(&[n, c, h / bs, bs, w / bs, bs])?
+                .transpose(&[0, 3, 5, 1, 2, 4])?
+                .reshape(&[n, c * bs * bs, h / bs, w / bs]),
+            DataFormat::Nhwc => self
+                .reshape(&[n, h / bs, bs, w / bs, bs, c])?
+                .transpose(&[0, 1, 3, 2, 4, 5])?
+                .reshape(&[n, h / bs, w / bs, bs * bs * c]),
+        }
+    }
+
+    /// Merge the first `num_dims` dimensions into a single one, e.g. `[b, t, c]` becomes
+    /// `[b * t, c]` for `num_dims = 2`.
+    pub fn flatten_leading(&self, num_dims: i64) -> Result<Self> {
+        let shape = self.array_shape()?;
+        let dims = shape.dims();
+        if num_dims < 1 || num_dims as usize > dims.len() {
+            Err(Error::IndexOutOfBounds { index: num_dims, rank: dims.len() })?
+        }
+        let (leading, trailing) = dims.split_at(num_dims as usize);
+        let mut new_dims = vec![leading.iter().product::<i64>()];
+        new_dims.extend_from_slice(trailing);
+        self.reshape(&new_dims)
+    }
+
+    /// Split the first dimension into `sizes`, this is the inverse of
+    /// [`XlaOp::flatten_leading`]. The product of `sizes` has to be equal to the size of the
+    /// first dimension.
+    pub fn unflatten_leading(&self, sizes: &[i64]) -> Result<Self> {
+        let shape = self.array_shape()?;
+        let dims = shape.dims();
+        let first_dim = match dims.first() {
+            None => Err(Error::UnexpectedNumberOfDims { expected: 1, got: 0, dims: vec![] })?,
+            Some(first_dim) => *first_dim,
+        };
+        let element_count = sizes.iter().product::<i64>();
+        if element_count != first_dim {
+            Err(Error::WrongElementCount {
+                dims: sizes.iter().map(|d| *d as usize).collect(),
+                element_count: first_dim as usize,
+            })?
+        }
+        let mut new_dims = sizes.to_vec();
+        new_dims.extend_from_slice(&dims[1..]);
+        self.reshape(&new_dims)
+    }
+
+    /// Pad each dimension by concatenating slices of the op itself, `padding` holds the number
+    /// of elements to add before and after each dimension. `index` returns the position of the
+    /// element to use for a padding element given the dimension size and the padding offset,
+    /// offsets being negative before the op and at least the dimension size after it.
+    fn pad_with_slices<F>(&self, padding: &[(i64, i64)], op_name: &str, index: F) -> Result<Self>
+    where
+        F: Fn(i64, i64) -> i64,
+    {
+        let shape = self.array_shape()?;
+        let dims = shape.dims();
+        if padding.len() != dims.len() {
+            Err(Error::UnexpectedNumberOfDims {
+                expected: dims.len(),
+                got: padding.len(),
+                dims: dims.to_vec(),
+            })?
+        }
+        let mut padded = self.clone();
+        for (dim, (&size, &(lo, hi))) in dims.iter().zip(padding.iter()).enumerate() {
+            let is_valid = |offset: i64| (0..size).contains(&index(size, offset));
+            if lo < 0
+                || hi < 0
+                || (lo > 0 && !is_valid(-lo))
+                || (hi > 0 && !is_valid(size + hi - 1))
+            {
+                let msg = format!("{op_name}: invalid padding {padding:?} for dims {dims:?}");
+                Err(super::xla_error(msg))?
+            }
+            let dim = dim as i64;
+            let slice = |i: i64| padded.slice_in_dim1(i, i + 1, dim);
+            let before = (-lo..0).map(|o| slice(index(size, o))).collect::<Result<Vec<_>>>()?;
+            let after =
+                (size..size + hi).map(|o| slice(index(size, o))).collect::<Result<Vec<_>>>()?;
+            if before.is_empty() && after.is_empty() {
+                continue;
+            }
+            let mut parts: Vec<&XlaOp> = before.iter().collect();
+            parts.push(&padded);
+            parts.extend(after.iter());
+            padded = parts[0].concat_in_dim(&parts[1..], dim)?;
+        }
+        Ok(padded)
+    }
+
+    /// Pad the op by reflecting it around its first and last elements, the edge elements are
+    /// not repeated, e.g. padding `[1, 2, 3]` with `(2, 1)` results in `[3, 2, 1, 2, 3, 2]`.
+    /// `padding` holds the number of elements to add before and after each dimension, these
+    /// have to be smaller than the dimension size. This matches the PyTorch `reflect` padding.
+    pub fn pad_reflect(&self, padding: &[(i64, i64)]) -> Result<Self> {
+        self.pad_with_slices(padding, "pad_reflect", |size, offset| {
+            if offset < 0 {
+                -offset
+            } else {
+                2 * (size - 1) - offset
+            }
+        })
+    }
+
+    /// Pad the op by repeating its first and last elements, e.g. padding `[1, 2, 3]` with
+    /// `(2, 1)` results in `[1, 1, 1, 2, 3, 3]`. `padding` holds the number of elements to add
+    /// before and after each dimension. This matches the PyTorch `replicate` padding.
+    pub fn pad_replicate(&self, padding: &[(i64, i64)]) -> Result<Self> {
+        self.pad_with_slices(padding, "pad_replicate", |size, offset| offset.max(0).min(size - 1))
+    }
+
+    /// Rearrange the dimensions of the op following an einops-style pattern such as
+    /// `"b (h w) c -> b h w c"`, see [`XlaOp::rearrange_with`].
+    pub fn rearrange(&self, pattern: &str) -> Result<Self> {
+        self.rearrange_with(pattern, &[])
+    }
+
+    /// Rearrange the dimensions of the op following an einops-style pattern, this supports the
+    /// subset of einops `rearrange` that lowers to a reshape and a transpose. Each side of the
+    /// pattern lists the dimensions as named axes, parentheses can be used to denote a dimension
+    /// made of multiple axes: on the left-hand side this splits a dimension, on the right-hand
+    /// side this merges axes. Every axis must appear exactly once on each side. The sizes of the
+    /// axes resulting from a split can be specified in `sizes`, for each split at most one size
+    /// can be left unspecified and gets inferred. Ellipsis and anonymous axes are not supported.
+    /// For example `"b (h w) c -> b w h c"` with `sizes` set to `[("h", 2)]` transforms an op
+    /// of shape `[b, 2 * w, c]` into an op of shape `[b, w, 2, c]`.
+    pub fn rearrange_with(&self, pattern: &str, sizes: &[(&str, i64)]) -> Result<Self> {
+        let err =
+            |msg: &str| super::xla_error(format!("invalid rearrange pattern {pattern:?}: {msg}"));
+        let (lhs, rhs) = match pattern.split_once("->") {
+            Some(sides) => sides,
+            None => Err(err("missing ->"))?,
+        };
+        let lhs = parse_rearrange_side(pattern, lhs)?;
+        let rhs = parse_rearrange_side(pattern, rhs)?;
+        let shape = self.array_shape()?;
+        let dims = shape.dims();
+        if lhs.len() != dims.len() {
+            Err(Error::UnexpectedNumberOfDims {
+                expected: lhs.len(),
+                got: dims.len(),
+                dims: dims.to_vec(),
+            })?
+        }
+        let bindings: HashMap<&str, i64> = sizes.iter().copied().collect();
+        let mut axis_sizes: HashMap<&str, i64> = HashMap::new();
+        let mut axes: Vec<&str> = vec![];
+        for (group, &dim) in lhs.iter().zip(dims.iter()) {
+            let known: i64 = group.iter().filter_map(|a| bindings.get(a.as_str())).product();
+            let unknown = group.iter().filter(|a| !bindings.contains_key(a.as_str())).count();
+            let inferred = match unknown {
+                0 if known == dim => 1,
+                1 if known > 0 && dim % known == 0 => dim / known,
+                0 | 1 => Err(err(&format!("axis sizes do not match dimension {dim}")))?,
+                _ => Err(err("more than one unknown axis size in a group"))?,
+            };
+            for axis in group.iter() {
+                let size = bindings.get(axis.as_str()).copied().unwrap_or(inferred);
+                if axis_sizes.insert(axis.as_str(), size).is_some() {
+                    Err(err(&format!("axis {axis} appears more than once")))?
+                }
+                axes.push(axis.as_str())
+            }
+        }
+        let rhs_axes: Vec<&str> = rhs.iter().flatten().map(|a| a.as_str()).collect();
+        let mut permutation = Vec::with_capacity(axes.len());
+        for axis in rhs_axes.iter() {
+            match axes.iter().position(|a| a == axis) {
+                Some(_) if permutation.iter().any(|&p| axes[p as usize] == *axis) => {
+                    Err(err(&format!("axis {axis} appears more than once")))?
+                }
+                Some(p) => permutation.push(p as i64),
+                None => Err(err(&format!("unknown axis {axis}")))?,
+            }
+        }
+        if permutation.len() != axes.len() {
+            Err(err("some axes are missing on the right-hand side"))?
+        }
+        let axis_dims: Vec<i64> = axes.iter().map(|a| axis_sizes[a]).collect();
+        let out_dims: Vec<i64> = rhs
+            .iter()
+            .map(|group| group.iter().map(|a| axis_sizes[a.as_str()]).product())
+            .collect();
+        let op = if axis_dims == dims { self.clone() } else { self.reshape(&axis_dims)? };
+        let is_identity = permutation.iter().enumerate().all(|(i, &p)| i as i64 == p);
+        let op = if is_identity { op } else { op.transpose(&permutation)? };
+        if out_dims == op.array_shape()?.dims() {
+            Ok(op)
+        } else {
+            op.reshape(&out_dims)
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Descriptions of how tensors get split across devices for SPMD partitioning.
+use crate::{Error, Result};
+
+/// An N-dimensional grid of devices with named axes, e.g. a `2x4` mesh with axes `data` and
+/// `model`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DeviceMesh {
+    axis_names: Vec<String>,
+    axis_sizes: Vec<usize>,
+    devices: Vec<usize>,
+}
+
+impl DeviceMesh {
+    /// Create a mesh from its axes and the ids of its devices, the devices are laid out in
+    /// row-major order over the axes. The number of devices has to be the product of the axis
+    /// sizes.
+    pub fn new(axes: &[(&str, usize)], devices: Vec<usize>) -> Result<Self> {
+        let axis_sizes: Vec<usize> = axes.iter().map(|(_, size)| *size).collect();
+        let element_count = axis_sizes.iter().product::<usize>();
+        if element_count != devices.len() {
+            Err(Error::WrongElementCount { dims: axis_sizes.clone(), element_count })?
+        }
+        let axis_names: Vec<String> = axes.iter().map(|(name, _)| name.to_string()).collect();
+        for (i, name) in axis_names.iter().enumerate() {
+            if axis_names[..i].contains(name) {
+                Err(super::xla_error(format!("mesh axis {name} appears more than once")))?
+            }
+        }
+        Ok(Self { axis_names, axis_sizes, devices })
+    }
+
+    /// Create a mesh using the devices `0..n` where `n` is the product of the axis sizes.
+    pub fn with_sequential_devices(axes: &[(&str, usize)]) -> Result<Self> {
+        let device_count = axes.iter().map(|(_, size)| *size).product::<usize>();
+        Self::new(axes, (0..device_count).collect())
+    }
+
+    pub fn axis_names(&self) -> &[String] {
+        &self.axis_names
+    }
+
+    pub fn axis_sizes(&self) -> &[usize] {
+        &self.axis_sizes
+    }
+
+    /// The device ids, in row-major order over the mesh axes.
+    pub fn devices(&self) -> &[usize] {
+        &self.devices
+    }
+
+    fn axis_index(&self, name: &str) -> Result<usize> {
+        match self.axis_names.iter().position(|n| n == name) {
+            Some(index) => Ok(index),
+            None => {
+                Err(super::xla_error(format!("unknown mesh axis {name}, {:?}", self.axis_names)))
+            }
+        }
+    }
+
+    /// The device ids with the mesh axes reordered following `permutation`.
+    fn transposed_devices(&self, permutation: &[usize]) -> Vec<usize> {
+        let rank = self.axis_sizes.len();
+        let mut strides = vec![1usize; rank];
+        for i in (0..rank.saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * self.axis_sizes[i + 1];
+        }
+        let out_sizes: Vec<usize> = permutation.iter().map(|&p| self.axis_sizes[p]).collect();
+        (0..self.devices.len())
+            .map(|out_index| {
+                let mut rem = out_index;
+                let mut src_index = 0;
+                for (i, &p) in permutation.iter().enumerate().rev() {
+                    src_index += (rem % out_sizes[i]) * strides[p];
+                    rem /= out_sizes[i];
+                }
+                self.devices[src_index]
+            })
+            .collect()
+    }
+}
+
+/// How the dimensions of a tensor are split into tiles, each tile being assigned to a device.
+/// The [`std::fmt::Display`] implementation returns the sharding attribute as used in the HLO
+/// text format, e.g. `{devices=[2,2]0,1,2,3}`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum OpSharding {
+    /// The whole tensor is available on every device.
+    Replicated,
+    /// The tensor is split in `tile_assignment_dims` tiles along each dimension, `devices` holds
+    /// the device of each tile in row-major order. When `last_tile_dim_replicate` is true, the
+    /// last tile dimension does not correspond to a tensor dimension and the tiles are
+    /// replicated over the devices of this dimension.
+    Tiled { tile_assignment_dims: Vec<usize>, devices: Vec<usize>, last_tile_dim_replicate: bool },
+}
+
+impl OpSharding {
+    /// A sharding splitting each tensor dimension over the mesh axis it is mapped to in
+    /// `dim_to_axis`, `None` meaning that the dimension is not split. Each mesh axis can be used
+    /// at most once, the tensor is replicated over the mesh axes that are not used.
+    pub fn tiled(mesh: &DeviceMesh, dim_to_axis: &[Option<&str>]) -> Result<Self> {
+        let mut tile_assignment_dims = Vec::with_capacity(dim_to_axis.len() + 1);
+        let mut permutation = Vec::with_capacity(mesh.axis_sizes.len());
+        for axis in dim_to_axis.iter() {
+            match axis {
+                None => tile_assignment_dims.push(1),
+                Some(axis) => {
+                    let index = mesh.axis_index(axis)?;
+                    if permutation.contains(&index) {
+                        Err(super::xla_error(format!("mesh axis {axis} is used more than once")))?
+                    }
+                    permutation.push(index);
+                    tile_assignment_dims.push(mesh.axis_sizes[index])
+                }
+            }
+        }
+        let unused_axes: Vec<usize> =
+            (0..mesh.axis_sizes.len()).filter(|i| !permutation.contains(i)).collect();
+        let replicated_size = unused_axes.iter().map(|&i| mesh.axis_sizes[i]).product::<usize>();
+        permutation.extend_from_slice(&unused_axes);
+        let last_tile_dim_replicate = replicated_size > 1;
+        if last_tile_dim_replicate {
+            tile_assignment_dims.push(replicated_size)
+        }
+        let devices = mesh.transposed_devices(&permutation);
+        Ok(Self::Tiled { tile_assignment_dims, devices, last_tile_dim_replicate })
+    }
+}
+
+impl std::fmt::Display for OpSharding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Replicated => write!(f, "{{replicated}}"),
+            Self::Tiled { tile_assignment_dims, devices, last_tile_dim_replicate } => {
+                let join =
+                    |v: &[usize]| v.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(",");
+                write!(f, "{{devices=[{}]{}", join(tile_assignment_dims), join(devices))?;
+                if *last_tile_dim_replicate {
+                    write!(f, " last_tile_dim_replicate")?
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
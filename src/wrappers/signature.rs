@@ -0,0 +1,57 @@
+//! The shapes of the inputs and output of a computation.
+use super::Shape;
+use crate::Result;
+
+/// The ordered parameter shapes and the result shape of a computation, this can be used to
+/// validate the arguments before executing the computation. A signature can be obtained from
+/// the [`super::ParameterMap`] used to create the parameters of the computation, see
+/// [`super::ParameterMap::signature`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Signature {
+    parameters: Vec<Shape>,
+    result: Shape,
+}
+
+impl Signature {
+    pub fn new(parameters: Vec<Shape>, result: Shape) -> Self {
+        Self { parameters, result }
+    }
+
+    /// The parameter shapes, ordered by parameter number.
+    pub fn parameters(&self) -> &[Shape] {
+        &self.parameters
+    }
+
+    /// The shape of the computation result.
+    pub fn result(&self) -> &Shape {
+        &self.result
+    }
+
+    /// Check that `inputs` has one shape per parameter and that each of them is equal to the
+    /// corresponding parameter shape, the returned error describes the first mismatch.
+    pub fn matches(&self, inputs: &[Shape]) -> Result<()> {
+        if inputs.len() != self.parameters.len() {
+            Err(super::xla_error(format!(
+                "expected {} arguments, got {}",
+                self.parameters.len(),
+                inputs.len()
+            )))?
+        }
+        for (index, (input, parameter)) in inputs.iter().zip(self.parameters.iter()).enumerate() {
+            if input == parameter {
+                continue;
+            }
+            let msg = match (input, parameter) {
+                (Shape::Array(got), Shape::Array(expected))
+                    if got.dims().len() != expected.dims().len() =>
+                {
+                    let (got_rank, expected_rank) = (got.dims().len(), expected.dims().len());
+                    format!("argument {index} has rank {got_rank}, expected {expected_rank}")
+                }
+                _ => format!("argument {index} has shape {input:?}, expected {parameter:?}"),
+            };
+            Err(super::xla_error(msg))?
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+//! Utilities that help validating hand-written computations.
+use super::{Literal, PjRtClient, PrimitiveType, XlaBuilder, XlaOp};
+use crate::Result;
+
+/// Estimate the jacobian of a computation with a scalar output using central finite differences.
+/// `build` is called once with a parameter op of the same shape as `input` and has to return
+/// the scalar output. The computation is compiled once and then run twice per element of
+/// `input`, perturbing this element by `eps` in both directions. The returned literal has the
+/// same shape and element type as `input`, and contains the estimated derivative of the output
+/// with respect to each input element.
+pub fn finite_difference_jacobian<F>(
+    client: &PjRtClient,
+    build: F,
+    input: &Literal,
+    eps: f64,
+) -> Result<Literal>
+where
+    F: FnOnce(&XlaOp) -> Result<XlaOp>,
+{
+    let shape = input.shape()?;
+    let ty = input.primitive_type()?;
+    let dims = input.array_shape()?.dims().to_vec();
+    let builder = XlaBuilder::new("finite_difference_jacobian");
+    let x = builder.parameter_s(0, &shape, "x")?;
+    let exe = build(&x)?.build()?.compile(client)?;
+    let eval = |values: &[f64]| -> Result<f64> {
+        let input = Literal::vec1(values).reshape(&dims)?.convert(ty)?;
+        let output = exe.execute(&[input])?[0][0].to_literal_sync()?;
+        output.convert(PrimitiveType::F64)?.get_first_element::<f64>()
+    };
+    let mut values = input.convert(PrimitiveType::F64)?.to_vec::<f64>()?;
+    let mut jacobian = Vec::with_capacity(values.len());
+    for i in 0..values.len() {
+        let v = values[i];
+        values[i] = v + eps;
+        let plus = eval(&values)?;
+        values[i] = v - eps;
+        let minus = eval(&values)?;
+        values[i] = v;
+        jacobian.push((plus - minus) / (2. * eps))
+    }
+    Literal::vec1(&jacobian).reshape(&dims)?.convert(ty)
+}
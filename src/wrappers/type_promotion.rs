@@ -0,0 +1,120 @@
+//! Element type promotion for binary operations.
+//!
+//! When combining two ops with different element types, both operands get converted to a
+//! common type with the following rules, these are close to the PyTorch and JAX ones:
+//! - `Pred` combined with any type results in the other type.
+//! - Two integer types with the same signedness result in the wider one.
+//! - A signed and an unsigned integer result in the signed type if it is strictly wider,
+//!   otherwise in the signed type twice as wide as the unsigned one, e.g. `U8` and `S8` result
+//!   in `S16`. `U64` combined with a signed type results in `F64`.
+//! - An integer and a floating point or complex type result in the floating point or complex
+//!   type.
+//! - Two floating point types result in the wider one, `F16` and `Bf16` result in `F32`.
+//! - A floating point and a complex type result in the complex type, using `C128` if the
+//!   floating point type is `F64`.
+//! - Two complex types result in the wider one.
+use super::{ElementType, XlaOp};
+use crate::Result;
+
+impl ElementType {
+    pub(super) fn is_unsigned_int(&self) -> bool {
+        matches!(self, Self::U8 | Self::U16 | Self::U32 | Self::U64)
+    }
+
+    pub(super) fn is_signed_int(&self) -> bool {
+        matches!(self, Self::S8 | Self::S16 | Self::S32 | Self::S64)
+    }
+
+    pub(super) fn is_float(&self) -> bool {
+        matches!(self, Self::F16 | Self::Bf16 | Self::F32 | Self::F64)
+    }
+
+    pub(super) fn is_complex(&self) -> bool {
+        matches!(self, Self::C64 | Self::C128)
+    }
+
+    /// The type that two operands of type `self` and `other` get converted to before being
+    /// combined, see the module documentation for the promotion rules.
+    pub fn promote(self, other: Self) -> Self {
+        let wider = |a: Self, b: Self| {
+            if a.element_size_in_bytes() >= b.element_size_in_bytes() {
+                a
+            } else {
+                b
+            }
+        };
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Pred, t) | (t, Self::Pred) => t,
+            (a, b) if a.is_complex() && b.is_complex() => wider(a, b),
+            (c, f) | (f, c) if c.is_complex() && f.is_float() => {
+                if f == Self::F64 {
+                    Self::C128
+                } else {
+                    c
+                }
+            }
+            (c, _) | (_, c) if c.is_complex() => c,
+            (Self::F16, Self::Bf16) | (Self::Bf16, Self::F16) => Self::F32,
+            (a, b) if a.is_float() && b.is_float() => wider(a, b),
+            (f, _) | (_, f) if f.is_float() => f,
+            (a, b) if a.is_signed_int() == b.is_signed_int() => wider(a, b),
+            (s, u) | (u, s) if s.is_signed_int() && u.is_unsigned_int() => {
+                if s.element_size_in_bytes() > u.element_size_in_bytes() {
+                    s
+                } else {
+                    match u {
+                        Self::U8 => Self::S16,
+                        Self::U16 => Self::S32,
+                        Self::U32 => Self::S64,
+                        _ => Self::F64,
+                    }
+                }
+            }
+            (a, b) => wider(a, b),
+        }
+    }
+}
+
+impl XlaOp {
+    /// Convert `self` and `rhs` to their common element type, as returned by
+    /// [`ElementType::promote`], so that they can be used in a binary operation. Operands that
+    /// already use the common type are returned unchanged.
+    pub fn promote_with(&self, rhs: &XlaOp) -> Result<(XlaOp, XlaOp)> {
+        let lhs_ty = self.array_shape()?.ty();
+        let rhs_ty = rhs.array_shape()?.ty();
+        let ty = lhs_ty.promote(rhs_ty);
+        let convert = |op: &XlaOp, op_ty: ElementType| {
+            if op_ty == ty {
+                Ok(op.clone())
+            } else {
+                op.convert(ty.primitive_type())
+            }
+        };
+        Ok((convert(self, lhs_ty)?, convert(rhs, rhs_ty)?))
+    }
+
+    /// Add two ops after promoting them to a common element type.
+    pub fn add_promoted(&self, rhs: &XlaOp) -> Result<XlaOp> {
+        let (lhs, rhs) = self.promote_with(rhs)?;
+        lhs.add_(&rhs)
+    }
+
+    /// Subtract two ops after promoting them to a common element type.
+    pub fn sub_promoted(&self, rhs: &XlaOp) -> Result<XlaOp> {
+        let (lhs, rhs) = self.promote_with(rhs)?;
+        lhs.sub_(&rhs)
+    }
+
+    /// Multiply two ops after promoting them to a common element type.
+    pub fn mul_promoted(&self, rhs: &XlaOp) -> Result<XlaOp> {
+        let (lhs, rhs) = self.promote_with(rhs)?;
+        lhs.mul_(&rhs)
+    }
+
+    /// Divide two ops after promoting them to a common element type.
+    pub fn div_promoted(&self, rhs: &XlaOp) -> Result<XlaOp> {
+        let (lhs, rhs) = self.promote_with(rhs)?;
+        lhs.div_(&rhs)
+    }
+}
@@ -0,0 +1,18 @@
+#![cfg(feature = "arrow")]
+use anyhow::Result;
+use arrow::array::{Array, Float64Array};
+use xla::Literal;
+
+#[test]
+fn arrow_round_trip() -> Result<()> {
+    let literal = Literal::vec1(&[1.5f64, -2., 3.25]);
+    let array = literal.to_arrow()?;
+    let floats = array.as_any().downcast_ref::<Float64Array>().expect("a Float64Array");
+    assert_eq!(floats.values(), &[1.5, -2., 3.25]);
+    let literal = Literal::from_arrow(array.as_ref())?;
+    assert_eq!(literal.array_shape()?.dims(), [3]);
+    assert_eq!(literal.to_vec::<f64>()?, [1.5, -2., 3.25]);
+    let matrix = Literal::vec1(&[1f64, 2., 3., 4.]).reshape(&[2, 2])?;
+    assert!(matrix.to_arrow().is_err());
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use xla::{ElementType, Literal, PjRtClient, VjpBuilder, XlaBuilder, XlaOp};
+
+const W: [f64; 4] = [0.5, -1., 2., 0.25];
+
+fn weights(builder: &XlaBuilder) -> xla::Result<XlaOp> {
+    builder.c1(&W)?.reshape(&[2, 2])
+}
+
+#[test]
+fn vjp_matches_finite_differences() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    let input = Literal::vec1(&[1.5f64, -0.5]).reshape(&[1, 2])?;
+
+    // sum((x.W)²) differentiated with respect to x.
+    let builder = XlaBuilder::new("test");
+    let x = builder.parameter(0, ElementType::F64, &[1, 2], "x")?;
+    let mut vjp = VjpBuilder::new();
+    let vx = vjp.var(&x);
+    let vw = vjp.var(&weights(&builder)?);
+    let y = vjp.matmul(vx, vw)?;
+    let y2 = vjp.mul(y, y)?;
+    let sum = vjp.reduce_sum(y2, &[0, 1])?;
+    let grads = vjp.grad(sum, &[vx])?;
+    let exe = grads[0].build()?.compile(&client)?;
+    let grad = exe.execute::<Literal>(&[input.clone()])?[0][0].to_literal_sync()?;
+
+    let expected = xla::finite_difference_jacobian(
+        &client,
+        |x| {
+            let y = x.matmul(&weights(&x.builder())?)?;
+            (&y * &y)?.reduce_sum(&[0, 1], false)
+        },
+        &input,
+        1e-5,
+    )?;
+    grad.assert_close(&expected, 1e-6, 1e-6)?;
+    Ok(())
+}
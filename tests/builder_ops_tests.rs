@@ -0,0 +1,41 @@
+use anyhow::Result;
+use xla::{ElementType, Literal, PjRtClient, XlaBuilder};
+
+#[test]
+fn reduce_with_sub_builder() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let reducer = builder.create_sub_builder("max");
+    assert_eq!(reducer.name(), "test.max");
+    let lhs = reducer.parameter(0, ElementType::F32, &[], "lhs")?;
+    let rhs = reducer.parameter(1, ElementType::F32, &[], "rhs")?;
+    let reducer = lhs.max(&rhs)?.build()?;
+    let x = builder.c1(&[1f32, 5., 3., 4., 2., 6.])?.reshape(&[2, 3])?;
+    let init = builder.c0(f32::NEG_INFINITY)?;
+    let max = x.reduce(init, &reducer, &[1], false)?;
+    let client = PjRtClient::cpu()?;
+    let exe = max.build()?.compile(&client)?;
+    let result = exe.execute::<Literal>(&[])?[0][0].to_literal_sync()?;
+    assert_eq!(result.to_vec::<f32>()?, [5., 6.]);
+    Ok(())
+}
+
+// This only checks the values: neither XlaComputation nor HloModuleProto expose the HLO text or
+// the list of instructions, so there is no way to assert that the splat path broadcasts a scalar
+// rather than embedding the full literal.
+#[test]
+fn constant_literal_splat() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    let zeros = Literal::vec1(&[0f32; 1000]).reshape(&[10, 100])?;
+    let mut values = [1f32; 1000];
+    values[999] = 2.;
+    let not_splat = Literal::vec1(&values);
+    for literal in [zeros, not_splat] {
+        let builder = XlaBuilder::new("test");
+        let op = builder.constant_literal_splat(&literal, 16)?;
+        let exe = op.build()?.compile(&client)?;
+        let result = exe.execute::<Literal>(&[])?[0][0].to_literal_sync()?;
+        assert_eq!(result.array_shape()?.dims(), literal.array_shape()?.dims());
+        assert_eq!(result.to_vec::<f32>()?, literal.to_vec::<f32>()?);
+    }
+    Ok(())
+}
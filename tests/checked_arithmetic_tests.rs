@@ -0,0 +1,43 @@
+use anyhow::Result;
+use xla::{Literal, PjRtClient, PrimitiveType, XlaBuilder, XlaOp};
+
+fn run(op: XlaOp, args: &[Literal]) -> Result<Literal> {
+    let client = PjRtClient::cpu()?;
+    let exe = op.build()?.compile(&client)?;
+    let result = exe.execute::<Literal>(args)?;
+    Ok(result[0][0].to_literal_sync()?)
+}
+
+fn flags(overflow: Literal) -> Result<Vec<u8>> {
+    Ok(overflow.convert(PrimitiveType::U8)?.to_vec::<u8>()?)
+}
+
+#[test]
+fn mul_checked() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let lhs = builder.c1(&[3i32, 65536, -1, i32::MIN, 0])?;
+    let rhs = builder.c1(&[7i32, 65536, i32::MIN, -1, i32::MAX])?;
+    let (result, overflow) = lhs.mul_checked(&rhs)?;
+    let (result, overflow) = run(builder.tuple(&[result, overflow])?, &[])?.to_tuple2()?;
+    assert_eq!(result.to_vec::<i32>()?[0], 21);
+    assert_eq!(flags(overflow)?, [0, 1, 1, 1, 0]);
+    Ok(())
+}
+
+#[test]
+fn add_and_sub_checked() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let lhs = builder.c1(&[1i32, i32::MAX, i32::MIN, -5])?;
+    let rhs = builder.c1(&[2i32, 1, 1, -7])?;
+    let (_, add_overflow) = lhs.add_checked(&rhs)?;
+    let (_, sub_overflow) = lhs.sub_checked(&rhs)?;
+    let unsigned = builder.c1(&[1u32, 0])?;
+    let (_, unsigned_overflow) = unsigned.sub_checked(&builder.c1(&[1u32, 1])?)?;
+    let (add_overflow, sub_overflow, unsigned_overflow) =
+        run(builder.tuple(&[add_overflow, sub_overflow, unsigned_overflow])?, &[])?.to_tuple3()?;
+    assert_eq!(flags(add_overflow)?, [0, 1, 0, 0]);
+    assert_eq!(flags(sub_overflow)?, [0, 0, 1, 0]);
+    assert_eq!(flags(unsigned_overflow)?, [0, 1]);
+    assert!(builder.c1(&[1f32])?.add_checked(&builder.c1(&[1f32])?).is_err());
+    Ok(())
+}
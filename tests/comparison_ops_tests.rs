@@ -0,0 +1,60 @@
+use anyhow::Result;
+use xla::{
+    CompareDirection, ComparisonType, Literal, PjRtClient, PrimitiveType, XlaBuilder, XlaOp,
+};
+
+fn run(op: XlaOp, args: &[Literal]) -> Result<Literal> {
+    let client = PjRtClient::cpu()?;
+    let exe = op.build()?.compile(&client)?;
+    let result = exe.execute::<Literal>(args)?;
+    Ok(result[0][0].to_literal_sync()?)
+}
+
+fn is_true(pred: &Literal) -> Result<bool> {
+    Ok(pred.convert(PrimitiveType::U8)?.get_first_element::<u8>()? == 1)
+}
+
+#[test]
+fn all_close() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., f32::INFINITY])?;
+    let y = builder.c1(&[1f32, 2.0001, f32::INFINITY])?;
+    let close = x.all_close(&y, 1e-3, 0.)?;
+    let not_close = x.all_close(&y, 1e-6, 0.)?;
+    let nan = builder.c1(&[1f32, 2., f32::NAN])?;
+    let nan_close = nan.all_close(&nan, 1e-3, 0.)?;
+    // The scalar gets broadcasted to the shape of the other op.
+    let broadcast = builder.c1(&[3i32, 3])?.all_close(&builder.c0(3f32)?, 0., 0.)?;
+    let (close, not_close, nan_close, broadcast) =
+        run(builder.tuple(&[close, not_close, nan_close, broadcast])?, &[])?.to_tuple4()?;
+    assert!(is_true(&close)?);
+    assert!(!is_true(&not_close)?);
+    assert!(!is_true(&nan_close)?);
+    assert!(is_true(&broadcast)?);
+    Ok(())
+}
+
+#[test]
+fn compare_total_order() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let values = [f32::NAN, 1., -0., f32::NEG_INFINITY, 0.];
+    let n = values.len() as i64;
+    let x = builder.c1(&values)?;
+    let lhs = x.broadcast_in_dim(&[n, n], &[1])?;
+    let rhs = x.broadcast_in_dim(&[n, n], &[0])?;
+    // The position of each element once sorted is the number of elements smaller than it.
+    let ranks = |comparison_type| -> xla::Result<XlaOp> {
+        lhs.compare(&rhs, CompareDirection::Lt, comparison_type)?
+            .convert(PrimitiveType::S64)?
+            .reduce_sum(&[1], false)
+    };
+    let total_order = ranks(ComparisonType::TotalOrder)?;
+    let float = ranks(ComparisonType::Float)?;
+    let nan_eq = x.compare(&x, CompareDirection::Eq, ComparisonType::TotalOrder)?;
+    let (total_order, float, nan_eq) =
+        run(builder.tuple(&[total_order, float, nan_eq])?, &[])?.to_tuple3()?;
+    assert_eq!(total_order.to_vec::<i64>()?, [4, 3, 1, 0, 2]);
+    assert_eq!(float.to_vec::<i64>()?, [0, 3, 1, 0, 1]);
+    assert_eq!(nan_eq.convert(PrimitiveType::U8)?.to_vec::<u8>()?, [1; 5]);
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use anyhow::Result;
+use xla::{CompositeBuilder, CompositeRegistry, Literal, PjRtClient, XlaBuilder, XlaOp};
+
+#[test]
+fn register_and_apply_composite() -> Result<()> {
+    let gelu: CompositeBuilder = Box::new(|_: &XlaBuilder, xs: &[XlaOp]| xs[0].gelu());
+    let add: CompositeBuilder = Box::new(|_: &XlaBuilder, xs: &[XlaOp]| &xs[0] + &xs[1]);
+    let relu: CompositeBuilder = Box::new(|_: &XlaBuilder, xs: &[XlaOp]| xs[0].relu());
+    let mut registry = CompositeRegistry::new();
+    registry.register_composite("gelu", gelu)?;
+    registry.register_composite("add", add)?;
+    assert!(registry.register_composite("gelu", relu).is_err());
+    let mut names: Vec<_> = registry.names().collect();
+    names.sort();
+    assert_eq!(names, ["add", "gelu"]);
+
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[-1f32, 0., 0.5, 2.])?;
+    let gelu = registry.apply_composite(&builder, "gelu", &[x.clone()])?;
+    let sum = registry.apply_composite(&builder, "add", &[x.clone(), gelu.clone()])?;
+    assert!(registry.apply_composite(&builder, "softmax", &[x.clone()]).is_err());
+    let expected = (&x + x.gelu()?)?;
+    let client = PjRtClient::cpu()?;
+    let exe = builder.tuple(&[sum, expected])?.build()?.compile(&client)?;
+    let result = exe.execute::<Literal>(&[])?[0][0].to_literal_sync()?;
+    let (sum, expected) = result.to_tuple2()?;
+    sum.assert_close(&expected, 0., 0.)?;
+    Ok(())
+}
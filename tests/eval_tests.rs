@@ -0,0 +1,18 @@
+use anyhow::Result;
+use xla::{Literal, PjRtClient};
+
+#[test]
+fn eval_multi() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    let x = Literal::vec1(&[1f32, 2., 3.]);
+    let y = Literal::vec1(&[4f32, 5., 6.]);
+    let outputs = xla::eval_multi(
+        &client,
+        |_, xs| Ok(vec![(&xs[0] + &xs[1])?, (&xs[0] * &xs[1])?]),
+        &[&x, &y],
+    )?;
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[0].to_vec::<f32>()?, [5., 7., 9.]);
+    assert_eq!(outputs[1].to_vec::<f32>()?, [4., 10., 18.]);
+    Ok(())
+}
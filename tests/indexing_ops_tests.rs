@@ -0,0 +1,89 @@
+use anyhow::Result;
+use xla::{ElementType, Literal, PjRtClient, XlaBuilder, XlaOp};
+
+fn run(op: XlaOp, args: &[Literal]) -> Result<Literal> {
+    let client = PjRtClient::cpu()?;
+    let exe = op.build()?.compile(&client)?;
+    let result = exe.execute::<Literal>(args)?;
+    Ok(result[0][0].to_literal_sync()?)
+}
+
+#[test]
+fn nonzero() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[0i32, 1, 0, 1])?.ne(&builder.zero(ElementType::S32)?)?;
+    let (indexes, count) = x.nonzero(3)?;
+    let (indexes, count) = run(builder.tuple(&[indexes, count])?, &[])?.to_tuple2()?;
+    assert_eq!(indexes.to_vec::<i64>()?, [1, 3, -1]);
+    assert_eq!(count.get_first_element::<i64>()?, 2);
+    Ok(())
+}
+
+#[test]
+fn nonzero_truncated() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[0f32, 2., 0., 0., 5., 6., 0., 1., 3.])?.reshape(&[3, 3])?;
+    let (indexes, count) = x.nonzero(4)?;
+    let (indexes, count) = run(builder.tuple(&[indexes, count])?, &[])?.to_tuple2()?;
+    assert_eq!(indexes.to_vec::<i64>()?, [1, 4, 5, 7]);
+    assert_eq!(count.get_first_element::<i64>()?, 4);
+    Ok(())
+}
+
+#[test]
+fn sparse_to_dense() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let indices = builder.c1(&[0i64, 1, 1, 2, 0, 1])?.reshape(&[3, 2])?;
+    let values = builder.c1(&[1f32, 2., 3.])?;
+    let default_value = builder.c0(-1f32)?;
+    let dense = builder.sparse_to_dense(&indices, &values, &[2, 3], &default_value)?;
+    let result = run(dense, &[])?;
+    assert_eq!(result.array_shape()?.dims(), [2, 3]);
+    assert_eq!(result.to_vec::<f32>()?, [-1., 3., -1., -1., -1., 2.]);
+    Ok(())
+}
+
+#[test]
+fn sparse_to_dense_duplicates_keep_last() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let indices = builder.c1(&[2i64, 0, 2])?.reshape(&[3, 1])?;
+    let values = builder.c1(&[1f32, 2., 3.])?;
+    let default_value = builder.c0(0f32)?;
+    let dense = builder.sparse_to_dense(&indices, &values, &[4], &default_value)?;
+    assert_eq!(run(dense, &[])?.to_vec::<f32>()?, [2., 0., 3., 0.]);
+    Ok(())
+}
+
+#[test]
+fn sparse_to_dense_clamps_coordinates() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let indices = builder.c1(&[0i64, 5, -1, 1])?.reshape(&[2, 2])?;
+    let values = builder.c1(&[1f32, 2.])?;
+    let default_value = builder.c0(0f32)?;
+    let dense = builder.sparse_to_dense(&indices, &values, &[3, 3], &default_value)?;
+    assert_eq!(run(dense, &[])?.to_vec::<f32>()?, [0., 2., 1., 0., 0., 0., 0., 0., 0.]);
+    Ok(())
+}
+
+#[test]
+fn gather_nd() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., 3., 4., 5., 6., 7., 8., 9.])?.reshape(&[3, 3])?;
+    let elements = x.gather_nd(&builder.c1(&[0i64, 1, 2, 2])?.reshape(&[2, 2])?)?;
+    let rows = x.gather_nd(&builder.c1(&[2i64, 0])?.reshape(&[2, 1])?)?;
+    assert!(x.gather_nd(&builder.c1(&[0i64, 1, 2])?.reshape(&[1, 3])?).is_err());
+    let (elements, rows) = run(builder.tuple(&[elements, rows])?, &[])?.to_tuple2()?;
+    assert_eq!(elements.to_vec::<f32>()?, [2., 9.]);
+    assert_eq!(rows.array_shape()?.dims(), [2, 3]);
+    assert_eq!(rows.to_vec::<f32>()?, [7., 8., 9., 1., 2., 3.]);
+    Ok(())
+}
+
+#[test]
+fn gather_nd_clamps_coordinates() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., 3., 4., 5., 6., 7., 8., 9.])?.reshape(&[3, 3])?;
+    let elements = x.gather_nd(&builder.c1(&[0i64, 3, -1, 1, 5, 5])?.reshape(&[3, 2])?)?;
+    assert_eq!(run(elements, &[])?.to_vec::<f32>()?, [3., 2., 9.]);
+    Ok(())
+}
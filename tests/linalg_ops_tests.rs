@@ -0,0 +1,89 @@
+use anyhow::Result;
+use xla::{ElementType, Literal, PjRtClient, PrimitiveType, XlaBuilder, XlaOp};
+
+fn run(op: XlaOp, args: &[Literal]) -> Result<Literal> {
+    let client = PjRtClient::cpu()?;
+    let exe = op.build()?.compile(&client)?;
+    let result = exe.execute::<Literal>(args)?;
+    Ok(result[0][0].to_literal_sync()?)
+}
+
+#[test]
+fn trace() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., 3., 4., 5., 6., 7., 8.])?.reshape(&[2, 2, 2])?;
+    let result = run(x.trace()?, &[])?;
+    assert_eq!(result.array_shape()?.dims(), [2]);
+    assert_eq!(result.to_vec::<f32>()?, [5., 13.]);
+    Ok(())
+}
+
+#[test]
+fn trace_checks_dims() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    assert!(builder.c1(&[1f32, 2.])?.trace().is_err());
+    assert!(builder.c1(&[1f32, 2., 3., 4., 5., 6.])?.reshape(&[2, 3])?.trace().is_err());
+    Ok(())
+}
+
+#[test]
+fn dot_general_mixed() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let values: Vec<f32> = (0..256).map(|i| 1. + i as f32 / 256.).collect();
+    let lhs = builder.c1(&values)?.reshape(&[1, 256])?.convert(PrimitiveType::Bf16)?;
+    let rhs = builder.c1(&values)?.reshape(&[256, 1])?.convert(PrimitiveType::Bf16)?;
+    let mixed = lhs.dot_general_mixed(&rhs, &[1], &[0], &[], &[], ElementType::F32)?;
+    assert_eq!(mixed.ty()?, PrimitiveType::F32);
+    let bf16 = lhs.dot_general(&rhs, &[1], &[0], &[], &[])?.convert(PrimitiveType::F32)?;
+    let (mixed, bf16) = run(builder.tuple(&[mixed, bf16])?, &[])?.to_tuple2()?;
+    let mixed = mixed.get_first_element::<f32>()?;
+    let bf16 = bf16.get_first_element::<f32>()?;
+    // The reference uses the original f32 values, before the rounding to bf16.
+    let reference: f32 = values.iter().map(|v| v * v).sum();
+    assert!((mixed - reference).abs() <= 1e-3 * reference.abs());
+    assert!((mixed - reference).abs() <= (bf16 - reference).abs());
+    Ok(())
+}
+
+#[test]
+fn align_for_matmul() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let lhs = builder.c1(&[1f32, 2., 3., 4., 5., 6.])?.reshape(&[2, 3])?;
+    // The rhs is given as [n, k] rather than [k, n].
+    let rhs = builder.c1(&[1f32, 0., 0., 0., 1., 0., 0., 0., 1., 1., 1., 1.])?.reshape(&[4, 3])?;
+    let (l, r) = lhs.align_for_matmul(&rhs)?;
+    assert_eq!(r.array_shape()?.dims(), [3, 4]);
+    let product = l.matmul(&r)?;
+    // The batch dimensions get broadcasted.
+    let batched = lhs.broadcast(&[5])?;
+    let (l, r) = batched.align_for_matmul(&rhs)?;
+    assert_eq!(l.array_shape()?.dims(), [5, 2, 3]);
+    assert_eq!(r.array_shape()?.dims(), [5, 3, 4]);
+    let batched_product = l.matmul(&r)?;
+    let (product, batched_product) =
+        run(builder.tuple(&[product, batched_product])?, &[])?.to_tuple2()?;
+    let expected = [1f32, 2., 3., 6., 4., 5., 6., 15.];
+    assert_eq!(product.array_shape()?.dims(), [2, 4]);
+    assert_eq!(product.to_vec::<f32>()?, expected);
+    assert_eq!(batched_product.to_vec::<f32>()?, expected.repeat(5));
+    assert!(lhs.align_for_matmul(&builder.c1(&[1f32; 20])?.reshape(&[4, 5])?).is_err());
+    Ok(())
+}
+
+#[test]
+fn diagonal_and_diag() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., 3., 4., 5., 6., 7., 8., 9.])?.reshape(&[3, 3])?;
+    let main = x.diagonal(0)?;
+    let upper = x.diagonal(1)?;
+    let lower = x.diagonal(-1)?;
+    let diag = builder.diag(&builder.c1(&[1f32, 2., 3.])?)?;
+    let (main, upper, lower, diag) =
+        run(builder.tuple(&[main, upper, lower, diag])?, &[])?.to_tuple4()?;
+    assert_eq!(main.to_vec::<f32>()?, [1., 5., 9.]);
+    assert_eq!(upper.to_vec::<f32>()?, [2., 6.]);
+    assert_eq!(lower.to_vec::<f32>()?, [4., 8.]);
+    assert_eq!(diag.array_shape()?.dims(), [3, 3]);
+    assert_eq!(diag.to_vec::<f32>()?, [1., 0., 0., 0., 2., 0., 0., 0., 3.]);
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use xla::{Literal, Shape};
+
+#[test]
+fn assert_close() -> Result<()> {
+    let actual = Literal::vec1(&[1f32, 2.0001, f32::NAN, f32::INFINITY]);
+    let expected = Literal::vec1(&[1f32, 2., f32::NAN, f32::INFINITY]);
+    actual.assert_close(&expected, 1e-3, 0.)?;
+    assert!(actual.assert_close(&expected, 1e-6, 0.).is_err());
+    let expected = Literal::vec1(&[1f32, 2., f32::NAN, f32::NEG_INFINITY]);
+    assert!(actual.assert_close(&expected, 1e-3, 0.).is_err());
+    let expected = Literal::vec1(&[1f32, 2.]);
+    assert!(actual.assert_close(&expected, 1e-3, 0.).is_err());
+    Ok(())
+}
+
+#[test]
+fn broadcast_scalar() -> Result<()> {
+    let literal = Literal::broadcast_scalar(1.5f32, &[2, 3])?;
+    assert_eq!(literal.array_shape()?.dims(), [2, 3]);
+    assert_eq!(literal.to_vec::<f32>()?, [1.5; 6]);
+    let literal = Literal::broadcast_scalar(7i64, &[])?;
+    assert_eq!(literal.to_vec::<i64>()?, [7]);
+    assert!(Literal::broadcast_scalar(0f32, &[2, -1]).is_err());
+    Ok(())
+}
+
+#[test]
+fn from_mmap() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("xla-from-mmap-{}.bin", std::process::id()));
+    let values = [1f32, -2., 3.5, 4.];
+    let data: Vec<u8> = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    std::fs::write(&path, data)?;
+    let literal = Literal::from_mmap(&path, &Shape::array::<f32>(vec![2, 2]));
+    let too_large = Literal::from_mmap(&path, &Shape::array::<f32>(vec![5]));
+    std::fs::remove_file(&path)?;
+    let literal = literal?;
+    assert_eq!(literal.array_shape()?.dims(), [2, 2]);
+    assert_eq!(literal.to_vec::<f32>()?, values);
+    assert!(too_large.is_err());
+    Ok(())
+}
+
+#[test]
+fn iter_leading() -> Result<()> {
+    let literal = Literal::vec1(&[1i32, 2, 3, 4, 5, 6]).reshape(&[3, 2])?;
+    let rows = literal.iter_leading().collect::<xla::Result<Vec<_>>>()?;
+    assert_eq!(rows.len(), 3);
+    for (i, row) in rows.iter().enumerate() {
+        assert_eq!(row.array_shape()?.dims(), [2]);
+        let i = i as i32;
+        assert_eq!(row.to_vec::<i32>()?, [2 * i + 1, 2 * i + 2]);
+    }
+    let scalar = Literal::scalar(1f32);
+    let items: Vec<_> = scalar.iter_leading().collect();
+    assert_eq!(items.len(), 1);
+    assert!(items[0].is_err());
+    Ok(())
+}
+
+#[test]
+fn bincount() -> Result<()> {
+    let literal = Literal::vec1(&[0i32, 1, 1, 2, 2, 2]);
+    assert_eq!(literal.bincount(3)?, [1, 2, 3]);
+    // Values outside of the bins are ignored.
+    let literal = Literal::vec1(&[-1i64, 0, 3, 1, 7]);
+    assert_eq!(literal.bincount(3)?, [1, 1, 0]);
+    assert!(Literal::vec1(&[1f32]).bincount(2).is_err());
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use xla::{Activation, ArrayElement, Literal, PjRtClient, XlaBuilder, XlaOp};
+
+fn run(op: XlaOp, args: &[Literal]) -> Result<Literal> {
+    let client = PjRtClient::cpu()?;
+    let exe = op.build()?.compile(&client)?;
+    let result = exe.execute::<Literal>(args)?;
+    Ok(result[0][0].to_literal_sync()?)
+}
+
+#[test]
+fn attention_with_causal_mask() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let q = builder.parameter(0, f32::TY, &[1, 1, 2, 1], "q")?;
+    let k = builder.parameter(1, f32::TY, &[1, 1, 2, 1], "k")?;
+    let v = builder.parameter(2, f32::TY, &[1, 1, 2, 1], "v")?;
+    // A rank 2 causal mask, broadcast over the batch and head dimensions.
+    let mask = builder.constant_r1(&[0f32, f32::NEG_INFINITY, 0., 0.])?.reshape(&[2, 2])?;
+    let att = q.scaled_dot_product_attention(&k, &v, Some(&mask), None)?;
+    let ones = Literal::vec1(&[1f32, 1.]).reshape(&[1, 1, 2, 1])?;
+    let v = Literal::vec1(&[2f32, 4.]).reshape(&[1, 1, 2, 1])?;
+    let result = run(att, &[ones.clone(), ones, v])?;
+    assert_eq!(result.array_shape()?.dims(), [1, 1, 2, 1]);
+    // The first position only attends to itself, the second one attends to both equally.
+    assert_eq!(result.to_vec::<f32>()?, [2., 3.]);
+    Ok(())
+}
+
+#[test]
+fn moments() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., 3., 4., 5., 5., 5., 5.])?.reshape(&[2, 4])?;
+    let (mean, var) = x.moments(&[-1], false)?;
+    let (mean, var) = run(builder.tuple(&[mean, var])?, &[])?.to_tuple2()?;
+    assert_eq!(mean.to_vec::<f32>()?, [2.5, 5.]);
+    assert_eq!(var.to_vec::<f32>()?, [1.25, 0.]);
+
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., 3., 4., 5., 5., 5., 5.])?.reshape(&[2, 4])?;
+    let (mean, var) = x.moments(&[0, 1], true)?;
+    let (mean, var) = run(builder.tuple(&[mean, var])?, &[])?.to_tuple2()?;
+    assert_eq!(mean.array_shape()?.dims(), [1, 1]);
+    assert_eq!(mean.to_vec::<f32>()?, [3.75]);
+    assert_eq!(var.to_vec::<f32>()?, [2.1875]);
+    Ok(())
+}
+
+#[test]
+fn local_response_norm() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., 3.])?.reshape(&[1, 1, 1, 3])?;
+    let y = x.local_response_norm(1, 1., 0.1, 0.5)?;
+    let result = run(y, &[])?;
+    assert_eq!(result.array_shape()?.dims(), [1, 1, 1, 3]);
+    // The windowed sums of squares are 1 + 4, 1 + 4 + 9 and 4 + 9.
+    let expected = [1. / 1.5f32.sqrt(), 2. / 2.4f32.sqrt(), 3. / 2.3f32.sqrt()];
+    result.assert_close(&Literal::vec1(&expected).reshape(&[1, 1, 1, 3])?, 1e-5, 0.)?;
+    Ok(())
+}
+
+#[test]
+fn fake_quant() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[-0.5f32, 0., 0.33, 0.61, 0.98, 1.2])?;
+    let y = x.fake_quant(8, 0., 1., false)?;
+    assert!(x.fake_quant(0, 0., 1., false).is_err());
+    assert!(x.fake_quant(1, 0., 1., true).is_err());
+    assert!(x.fake_quant(8, 1., 1., false).is_err());
+    assert!(x.fake_quant(8, 0., f64::NAN, false).is_err());
+    let result = run(y, &[])?;
+    // The values get clamped to [0, 1] and snapped to the closest multiple of 1/255.
+    let expected: Vec<f32> = [0f32, 0., 84., 156., 250., 255.].iter().map(|v| v / 255.).collect();
+    result.assert_close(&Literal::vec1(&expected), 1e-5, 1e-6)?;
+    Ok(())
+}
+
+#[test]
+fn linear_with_relu() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., -1., 0.5])?.reshape(&[2, 2])?;
+    let weight = builder.c1(&[1f32, -1., 2., 0.5, -2., 1.])?.reshape(&[2, 3])?;
+    let bias = builder.c1(&[0.5f32, -1., 0.])?;
+    let ys = x.linear(&weight, Some(&bias), Some(Activation::Relu))?;
+    let no_bias = x.linear(&weight, None, None)?;
+    let (ys, no_bias) = run(builder.tuple(&[ys, no_bias])?, &[])?.to_tuple2()?;
+    assert_eq!(no_bias.to_vec::<f32>()?, [2., -5., 4., -0.75, 0., -1.5]);
+    assert_eq!(ys.array_shape()?.dims(), [2, 3]);
+    assert_eq!(ys.to_vec::<f32>()?, [2.5, 0., 4., 0., 0., 0.]);
+    Ok(())
+}
+
+#[test]
+fn soft_clip() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let values: Vec<f32> = (-40..=40).map(|v| v as f32 / 4.).collect();
+    let x = builder.c1(&values)?;
+    let result = run(x.soft_clip(-1., 1., 20.)?, &[])?.to_vec::<f32>()?;
+    for (&x, &y) in values.iter().zip(result.iter()) {
+        assert!(y.abs() <= 1. + 1e-6, "{x} {y}");
+        if x.abs() <= 0.5 {
+            // Values well inside the range are nearly unchanged.
+            assert!((x - y).abs() < 1e-4, "{x} {y}");
+        } else if x.abs() >= 2. {
+            // Values far outside the range are squashed to the bounds.
+            assert!((x.signum() - y).abs() < 1e-4, "{x} {y}");
+        }
+    }
+    // The transition is continuous and monotonic.
+    for w in result.windows(2) {
+        assert!(w[0] <= w[1] && w[1] - w[0] <= 0.25, "{w:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn add_bias() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.zero(f32::TY)?.broadcast(&[1, 2, 2, 3])?;
+    let bias = builder.c1(&[1f32, 2., 3.])?;
+    let nhwc = x.add_bias(&bias, -1)?;
+    let nchw = builder.zero(f32::TY)?.broadcast(&[1, 3, 2, 2])?.add_bias(&bias, 1)?;
+    assert!(x.add_bias(&bias, 1).is_err());
+    assert!(x.add_bias(&bias, 4).is_err());
+    assert!(x.add_bias(&bias.reshape(&[1, 3])?, 3).is_err());
+    let (nhwc, nchw) = run(builder.tuple(&[nhwc, nchw])?, &[])?.to_tuple2()?;
+    assert_eq!(nhwc.array_shape()?.dims(), [1, 2, 2, 3]);
+    assert_eq!(nhwc.to_vec::<f32>()?, [1., 2., 3.].repeat(4));
+    assert_eq!(nchw.to_vec::<f32>()?, [1., 1., 1., 1., 2., 2., 2., 2., 3., 3., 3., 3.]);
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use xla::{ElementType, Literal, ParameterMap, PjRtClient, XlaBuilder};
+
+#[test]
+fn bind() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let mut parameters = ParameterMap::new();
+    parameters.parameter(&builder, ElementType::F32, &[], "x")?;
+    parameters.parameter(&builder, ElementType::F32, &[], "y")?;
+    assert!(parameters.parameter(&builder, ElementType::F32, &[], "x").is_err());
+    assert_eq!(parameters.index("y"), Some(1));
+    assert_eq!(parameters.names(), ["x", "y"]);
+    let (x, y) = (0, 1);
+    let args = HashMap::from([("y", &y), ("x", &x)]);
+    assert_eq!(parameters.bind(&args)?, [&0, &1]);
+    let missing = HashMap::from([("x", &x)]);
+    assert!(parameters.bind(&missing).is_err());
+    let extra = HashMap::from([("x", &x), ("y", &y), ("z", &y)]);
+    assert!(parameters.bind(&extra).is_err());
+    Ok(())
+}
+
+#[test]
+fn execute_named() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    let builder = XlaBuilder::new("test");
+    let mut parameters = ParameterMap::new();
+    let x = parameters.parameter(&builder, ElementType::F32, &[2], "x")?;
+    let y = parameters.parameter(&builder, ElementType::F32, &[2], "y")?;
+    let exe = (x - y)?.build()?.compile(&client)?;
+    let x = client.buffer_from_host_literal(None, &Literal::vec1(&[5f32, 7.]))?;
+    let y = client.buffer_from_host_literal(None, &Literal::vec1(&[1f32, 2.]))?;
+    let args = HashMap::from([("y", &y), ("x", &x)]);
+    let result = exe.execute_named(&parameters, &args)?[0][0].to_literal_sync()?;
+    assert_eq!(result.to_vec::<f32>()?, [4., 5.]);
+    Ok(())
+}
+
+#[test]
+fn large_constant() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    let builder = XlaBuilder::new("test");
+    let mut parameters = ParameterMap::new();
+    let x = parameters.parameter(&builder, ElementType::F32, &[10_000], "x")?;
+    let values: Vec<f32> = (0..10_000).map(|v| v as f32).collect();
+    let weights = parameters.large_constant(&builder, "weights", Literal::vec1(&values))?;
+    // The constant is passed as a parameter rather than being embedded in the computation.
+    assert_eq!(parameters.names(), ["x", "weights"]);
+    assert_eq!(parameters.constants().len(), 1);
+    assert_eq!(parameters.constants()[0].name(), "weights");
+    let exe = (x + weights)?.build()?.compile(&client)?;
+    let constants = parameters.upload_constants(&client)?;
+    let x = client.buffer_from_host_literal(None, &Literal::vec1(&[1f32; 10_000]))?;
+    let mut args: HashMap<&str, &_> = constants.iter().map(|(k, v)| (k.as_str(), v)).collect();
+    args.insert("x", &x);
+    let result = exe.execute_named(&parameters, &args)?[0][0].to_literal_sync()?;
+    let expected: Vec<f32> = values.iter().map(|v| v + 1.).collect();
+    assert_eq!(result.to_vec::<f32>()?, expected);
+    Ok(())
+}
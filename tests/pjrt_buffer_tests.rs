@@ -0,0 +1,30 @@
+use anyhow::Result;
+use xla::{Literal, PjRtClient};
+
+#[test]
+fn reshape() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    let buffer =
+        client.buffer_from_host_literal(None, &Literal::vec1(&[1f32, 2., 3., 4., 5., 6.]))?;
+    let reshaped = buffer.reshape(&[2, 3])?;
+    let literal = reshaped.to_literal_sync()?;
+    assert_eq!(literal.array_shape()?.dims(), [2, 3]);
+    assert_eq!(literal.to_vec::<f32>()?, [1., 2., 3., 4., 5., 6.]);
+    assert!(buffer.reshape(&[4, 2]).is_err());
+    Ok(())
+}
+
+#[test]
+fn reshape_rejects_other_devices() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    let mut devices = client.addressable_devices();
+    // The cpu client may only expose a single device.
+    if devices.len() > 1 {
+        let literal = Literal::vec1(&[1f32, 2., 3., 4.]);
+        let buffer = client.buffer_from_host_literal(Some(&devices[1]), &literal)?;
+        assert!(buffer.reshape(&[2, 2]).is_err());
+        let buffer = buffer.copy_to_device(devices.swap_remove(0))?;
+        assert_eq!(buffer.reshape(&[2, 2])?.to_literal_sync()?.array_shape()?.dims(), [2, 2]);
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anyhow::Result;
+use xla::{ElementType, Literal, PjRtClient, PrimitiveType, Shape, XlaBuilder, XlaOp};
+
+#[test]
+fn outfeed_reader() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    let builder = XlaBuilder::new("test");
+    let init = builder.tuple(&[builder.c0(0i64)?])?;
+    let state_shape = builder.get_shape(&init)?;
+    let cond = builder.create_sub_builder("cond");
+    let state = cond.parameter_s(0, &state_shape, "state")?;
+    let cond = state.get_tuple_element(0)?.lt(&cond.c0(3i64)?)?.build()?;
+    let body = builder.create_sub_builder("body");
+    let state = body.parameter_s(0, &state_shape, "state")?;
+    let counter = state.get_tuple_element(0)?;
+    // Outfeed the counter on each iteration of the loop.
+    counter.outfeed(PrimitiveType::S64, &[], "");
+    let body = body.tuple(&[(counter + body.one(ElementType::S64)?)?])?.build()?;
+    let exe = XlaOp::while_(cond, body, init)?.build()?.compile(&client)?;
+    let devices = client.addressable_devices();
+    let tuple_shape = Shape::tuple(vec![Shape::array::<i64>(vec![])]);
+    assert!(client.outfeed_reader(&devices[0], &tuple_shape).is_err());
+    let reader = client.outfeed_reader(&devices[0], &Shape::array::<i64>(vec![]))?;
+    let result = exe.execute::<Literal>(&[])?;
+    let values = (0..3)
+        .map(|_| reader.read()?.get_first_element::<i64>())
+        .collect::<xla::Result<Vec<_>>>()?;
+    assert_eq!(values, [0, 1, 2]);
+    let result = result[0][0].to_literal_sync()?.to_tuple1()?;
+    assert_eq!(result.get_first_element::<i64>()?, 3);
+    Ok(())
+}
+
+#[test]
+fn process_topology() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    assert_eq!(client.process_index(), 0);
+    assert_eq!(client.process_count(), 1);
+    assert_eq!(client.global_device_count(), client.addressable_device_count());
+    Ok(())
+}
+
+#[test]
+fn live_buffers() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    assert_eq!(client.live_buffer_count(), 0);
+    let x = client.buffer_from_host_literal(None, &Literal::vec1(&[1f32, 2., 3., 4.]))?;
+    let y = client.buffer_from_host_buffer(&[1i64, 2], &[2], None)?;
+    assert_eq!(client.live_buffer_count(), 2);
+    assert_eq!(client.live_buffer_bytes(), 32);
+    let builder = XlaBuilder::new("test");
+    let p = builder.parameter(0, ElementType::F32, &[4], "p")?;
+    let exe = (&p + &p)?.build()?.compile(&client)?;
+    let outputs = exe.execute_b(&[&x])?;
+    assert_eq!(client.live_buffer_count(), 3);
+    drop(outputs);
+    drop(x);
+    assert_eq!(client.live_buffer_count(), 1);
+    assert_eq!(client.live_buffer_bytes(), 16);
+    drop(y);
+    assert_eq!(client.live_buffer_count(), 0);
+    assert_eq!(client.live_buffer_bytes(), 0);
+    Ok(())
+}
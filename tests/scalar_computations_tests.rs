@@ -0,0 +1,26 @@
+use anyhow::Result;
+use xla::{ElementType, Literal, PjRtClient, ScalarComputations, XlaBuilder};
+
+#[test]
+fn scalar_computations_are_cached() -> Result<()> {
+    let mut cache = ScalarComputations::new();
+    let add = cache.add(ElementType::F32)? as *const _;
+    assert!(std::ptr::eq(add, cache.add(ElementType::F32)?));
+    cache.max(ElementType::F32)?;
+    cache.add(ElementType::S32)?;
+    assert_eq!(cache.len(), 3);
+
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., 3., 4.])?.reshape(&[2, 2])?;
+    let add = cache.add(ElementType::F32)?;
+    let rows = x.reduce(builder.zero(ElementType::F32)?, add, &[1], false)?;
+    let cols = x.reduce(builder.zero(ElementType::F32)?, add, &[0], false)?;
+    assert_eq!(cache.len(), 3);
+    let client = PjRtClient::cpu()?;
+    let exe = builder.tuple(&[rows, cols])?.build()?.compile(&client)?;
+    let result = exe.execute::<Literal>(&[])?[0][0].to_literal_sync()?;
+    let (rows, cols) = result.to_tuple2()?;
+    assert_eq!(rows.to_vec::<f32>()?, [3., 7.]);
+    assert_eq!(cols.to_vec::<f32>()?, [4., 6.]);
+    Ok(())
+}
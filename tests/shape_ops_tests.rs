@@ -0,0 +1,152 @@
+use anyhow::Result;
+use xla::{ArrayElement, DataFormat, Literal, PjRtClient, XlaBuilder, XlaOp};
+
+fn run(op: XlaOp, args: &[Literal]) -> Result<Literal> {
+    let client = PjRtClient::cpu()?;
+    let exe = op.build()?.compile(&client)?;
+    let result = exe.execute::<Literal>(args)?;
+    Ok(result[0][0].to_literal_sync()?)
+}
+
+#[test]
+fn view_as_complex_round_trip() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.parameter(0, f32::TY, &[3, 2], "x")?;
+    let y = x.view_as_complex()?;
+    assert_eq!(y.array_shape()?.dims(), [3]);
+    let y = y.view_as_real()?;
+    let input = Literal::vec1(&[1f32, -2., 0., 3.5, -4., 0.]).reshape(&[3, 2])?;
+    let result = run(y, &[input])?;
+    assert_eq!(result.array_shape()?.dims(), [3, 2]);
+    assert_eq!(result.to_vec::<f32>()?, [1., -2., 0., 3.5, -4., 0.]);
+    Ok(())
+}
+
+#[test]
+fn view_as_complex_checks_last_dim() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.parameter(0, f32::TY, &[3, 4], "x")?;
+    assert!(x.view_as_complex().is_err());
+    let x = builder.parameter(1, f32::TY, &[], "x")?;
+    assert!(x.view_as_complex().is_err());
+    Ok(())
+}
+
+#[test]
+fn depth_to_space_round_trip() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.parameter(0, f32::TY, &[2, 8, 3, 2], "x")?;
+    let y = x.depth_to_space(2, DataFormat::Nchw)?;
+    assert_eq!(y.array_shape()?.dims(), [2, 2, 6, 4]);
+    let y = y.space_to_depth(2, DataFormat::Nchw)?;
+    let values: Vec<f32> = (0..96).map(|v| v as f32).collect();
+    let input = Literal::vec1(&values).reshape(&[2, 8, 3, 2])?;
+    let result = run(y, &[input])?;
+    assert_eq!(result.array_shape()?.dims(), [2, 8, 3, 2]);
+    assert_eq!(result.to_vec::<f32>()?, values);
+    Ok(())
+}
+
+#[test]
+fn depth_to_space() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., 3., 4.])?.reshape(&[1, 1, 1, 4])?;
+    let y = x.depth_to_space(2, DataFormat::Nhwc)?;
+    let result = run(y, &[])?;
+    assert_eq!(result.array_shape()?.dims(), [1, 2, 2, 1]);
+    assert_eq!(result.to_vec::<f32>()?, [1., 2., 3., 4.]);
+    Ok(())
+}
+
+#[test]
+fn depth_to_space_checks_block_size() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.parameter(0, f32::TY, &[1, 8, 2, 2], "x")?;
+    assert!(x.depth_to_space(0, DataFormat::Nchw).is_err());
+    assert!(x.depth_to_space(-2, DataFormat::Nchw).is_err());
+    assert!(x.depth_to_space(3, DataFormat::Nchw).is_err());
+    assert!(x.space_to_depth(0, DataFormat::Nchw).is_err());
+    assert!(x.space_to_depth(3, DataFormat::Nchw).is_err());
+    Ok(())
+}
+
+#[test]
+fn flatten_leading_round_trip() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.parameter(0, f32::TY, &[2, 3, 4], "x")?;
+    let y = x.flatten_leading(2)?;
+    assert_eq!(y.array_shape()?.dims(), [6, 4]);
+    let y = y.unflatten_leading(&[2, 3])?;
+    assert_eq!(y.array_shape()?.dims(), [2, 3, 4]);
+    assert!(x.flatten_leading(3)?.unflatten_leading(&[4, 2]).is_err());
+    assert!(x.flatten_leading(0).is_err());
+    assert!(x.flatten_leading(4).is_err());
+    let values: Vec<f32> = (0..24).map(|v| v as f32).collect();
+    let result = run(y, &[Literal::vec1(&values).reshape(&[2, 3, 4])?])?;
+    assert_eq!(result.to_vec::<f32>()?, values);
+    Ok(())
+}
+
+#[test]
+fn pad_reflect_and_replicate() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1f32, 2., 3.])?;
+    let reflect = x.pad_reflect(&[(1, 1)])?;
+    let reflect2 = x.pad_reflect(&[(2, 1)])?;
+    let replicate = x.pad_replicate(&[(2, 1)])?;
+    let m = builder.c1(&[1f32, 2., 3., 4.])?.reshape(&[2, 2])?;
+    let reflect_2d = m.pad_reflect(&[(1, 0), (0, 1)])?;
+    assert!(x.pad_reflect(&[(3, 0)]).is_err());
+    assert!(x.pad_replicate(&[(1, 1), (1, 1)]).is_err());
+    let result = run(builder.tuple(&[reflect, reflect2, replicate, reflect_2d])?, &[])?;
+    let (reflect, reflect2, replicate, reflect_2d) = result.to_tuple4()?;
+    assert_eq!(reflect.to_vec::<f32>()?, [2., 1., 2., 3., 2.]);
+    assert_eq!(reflect2.to_vec::<f32>()?, [3., 2., 1., 2., 3., 2.]);
+    assert_eq!(replicate.to_vec::<f32>()?, [1., 1., 1., 2., 3., 3.]);
+    assert_eq!(reflect_2d.array_shape()?.dims(), [3, 3]);
+    assert_eq!(reflect_2d.to_vec::<f32>()?, [3., 4., 3., 1., 2., 1., 3., 4., 3.]);
+    Ok(())
+}
+
+#[test]
+fn rearrange() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[0f32, 1., 2., 3., 4., 5.])?.reshape(&[2, 3, 1])?;
+    let merged = x.rearrange("a b c -> a (b c)")?;
+    assert_eq!(merged.array_shape()?.dims(), [2, 3]);
+    let transposed = x.rearrange("a b c -> (b a) c")?;
+    assert_eq!(transposed.array_shape()?.dims(), [6, 1]);
+    let y = builder.c1(&[0f32, 1., 2., 3.])?.reshape(&[4, 1])?;
+    let split = y.rearrange_with("(h w) c -> w h c", &[("h", 2)])?;
+    assert_eq!(split.array_shape()?.dims(), [2, 2, 1]);
+    let result = run(builder.tuple(&[merged, transposed, split])?, &[])?;
+    let (merged, transposed, split) = result.to_tuple3()?;
+    assert_eq!(merged.to_vec::<f32>()?, [0., 1., 2., 3., 4., 5.]);
+    assert_eq!(transposed.to_vec::<f32>()?, [0., 3., 1., 4., 2., 5.]);
+    assert_eq!(split.to_vec::<f32>()?, [0., 2., 1., 3.]);
+    Ok(())
+}
+
+#[test]
+fn rearrange_invalid_patterns() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.parameter(0, f32::TY, &[2, 6], "x")?;
+    for pattern in [
+        "a b",
+        "a (b -> a b",
+        "a b) -> a b",
+        "a (b (c)) -> a b c",
+        "a b -> a c",
+        "a b -> a b b",
+        "a b -> a",
+        "a a -> a a",
+        "a b c -> a b c",
+        "a (b c) -> a b c",
+        "a b- -> a b-",
+    ] {
+        assert!(x.rearrange(pattern).is_err(), "{pattern}");
+    }
+    assert!(x.rearrange_with("a (b c) -> a b c", &[("b", 4)]).is_err());
+    assert!(x.rearrange_with("a (b c) -> a c b", &[("b", 3)]).is_ok());
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use anyhow::Result;
+use xla::{DeviceMesh, OpSharding};
+
+#[test]
+fn tiled_sharding() -> Result<()> {
+    let mesh = DeviceMesh::with_sequential_devices(&[("x", 2), ("y", 2)])?;
+    assert_eq!(mesh.devices(), [0, 1, 2, 3]);
+    let sharding = OpSharding::tiled(&mesh, &[Some("x"), Some("y")])?;
+    assert_eq!(sharding.to_string(), "{devices=[2,2]0,1,2,3}");
+    let sharding = OpSharding::tiled(&mesh, &[Some("y"), Some("x")])?;
+    assert_eq!(sharding.to_string(), "{devices=[2,2]0,2,1,3}");
+    let sharding = OpSharding::tiled(&mesh, &[Some("y"), None])?;
+    assert_eq!(sharding.to_string(), "{devices=[2,1,2]0,2,1,3 last_tile_dim_replicate}");
+    let sharding = OpSharding::tiled(&mesh, &[None, None])?;
+    assert_eq!(sharding.to_string(), "{devices=[1,1,4]0,1,2,3 last_tile_dim_replicate}");
+    assert_eq!(OpSharding::Replicated.to_string(), "{replicated}");
+    assert!(OpSharding::tiled(&mesh, &[Some("z"), None]).is_err());
+    assert!(OpSharding::tiled(&mesh, &[Some("x"), Some("x")]).is_err());
+    Ok(())
+}
+
+#[test]
+fn device_mesh() -> Result<()> {
+    let mesh = DeviceMesh::new(&[("data", 2), ("model", 3)], vec![5, 4, 3, 2, 1, 0])?;
+    assert_eq!(mesh.axis_names(), ["data", "model"]);
+    assert_eq!(mesh.axis_sizes(), [2, 3]);
+    let sharding = OpSharding::tiled(&mesh, &[Some("model"), Some("data")])?;
+    assert_eq!(sharding.to_string(), "{devices=[3,2]5,2,4,1,3,0}");
+    assert!(DeviceMesh::new(&[("data", 2), ("model", 3)], vec![0, 1, 2, 3]).is_err());
+    assert!(DeviceMesh::with_sequential_devices(&[("data", 2), ("data", 2)]).is_err());
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use xla::{ElementType, ParameterMap, Shape, Signature, XlaBuilder};
+
+#[test]
+fn matches() -> Result<()> {
+    let signature = Signature::new(
+        vec![Shape::array::<f32>(vec![2, 3]), Shape::array::<i64>(vec![])],
+        Shape::array::<f32>(vec![2]),
+    );
+    signature.matches(&[Shape::array::<f32>(vec![2, 3]), Shape::array::<i64>(vec![])])?;
+    let err = signature
+        .matches(&[Shape::array::<f32>(vec![6]), Shape::array::<i64>(vec![])])
+        .unwrap_err();
+    assert!(err.to_string().contains("argument 0 has rank 1, expected 2"), "{err}");
+    let err = signature
+        .matches(&[Shape::array::<f32>(vec![2, 3]), Shape::array::<i32>(vec![])])
+        .unwrap_err();
+    assert!(err.to_string().contains("argument 1 has shape"), "{err}");
+    let err = signature.matches(&[Shape::array::<f32>(vec![2, 3])]).unwrap_err();
+    assert!(err.to_string().contains("expected 2 arguments, got 1"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn signature_from_parameter_map() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let mut parameters = ParameterMap::new();
+    let x = parameters.parameter(&builder, ElementType::F32, &[2, 3], "x")?;
+    let signature = parameters.signature(&x.reduce_sum(&[1], false)?)?;
+    assert_eq!(signature.parameters(), [Shape::array::<f32>(vec![2, 3])]);
+    assert_eq!(signature.result(), &Shape::array::<f32>(vec![2]));
+    Ok(())
+}
@@ -0,0 +1,16 @@
+use anyhow::Result;
+use xla::{Literal, PjRtClient};
+
+#[test]
+fn finite_difference_jacobian() -> Result<()> {
+    let client = PjRtClient::cpu()?;
+    let input = Literal::vec1(&[-1.5f64, 0., 0.5, 2.]);
+    let jacobian = xla::finite_difference_jacobian(
+        &client,
+        |x| (x * x)?.reduce_sum(&[0], false),
+        &input,
+        1e-4,
+    )?;
+    jacobian.assert_close(&Literal::vec1(&[-3f64, 0., 1., 4.]), 1e-6, 1e-6)?;
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use xla::{ElementType, Literal, PjRtClient, PrimitiveType, XlaBuilder};
+
+#[test]
+fn promote() {
+    use ElementType::*;
+    let cases = [
+        (Pred, S8, S8),
+        (S8, S32, S32),
+        (U16, U8, U16),
+        (U8, S8, S16),
+        (U16, S64, S64),
+        (U32, S32, S64),
+        (U64, S8, F64),
+        (S64, F16, F16),
+        (F16, Bf16, F32),
+        (F32, F64, F64),
+        (F32, C64, C64),
+        (F64, C64, C128),
+        (C64, C128, C128),
+    ];
+    for (lhs, rhs, expected) in cases {
+        assert_eq!(lhs.promote(rhs), expected, "{lhs:?} {rhs:?}");
+        assert_eq!(rhs.promote(lhs), expected, "{rhs:?} {lhs:?}");
+    }
+}
+
+#[test]
+fn add_promoted() -> Result<()> {
+    let builder = XlaBuilder::new("test");
+    let x = builder.c1(&[1i32, 2, 3])?;
+    let y = builder.c1(&[0.5f32, 0.25, 0.125])?;
+    let sum = x.add_promoted(&y)?;
+    assert_eq!(sum.ty()?, PrimitiveType::F32);
+    let z = builder.c1(&[10i64, 20, 30])?;
+    let int_sum = x.add_promoted(&z)?;
+    assert_eq!(int_sum.ty()?, PrimitiveType::S64);
+    let client = PjRtClient::cpu()?;
+    let exe = builder.tuple(&[sum, int_sum])?.build()?.compile(&client)?;
+    let result = exe.execute::<Literal>(&[])?[0][0].to_literal_sync()?;
+    let (sum, int_sum) = result.to_tuple2()?;
+    assert_eq!(sum.to_vec::<f32>()?, [1.5, 2.25, 3.125]);
+    assert_eq!(int_sum.to_vec::<i64>()?, [11, 22, 33]);
+    Ok(())
+}